@@ -1,6 +1,12 @@
 use yew::prelude::*;
 
+use super::program::Breath;
+
 /// This represents a timer `Clock` with a progress bar and a clock face.
+///
+/// The inner ring shows the progress of the current interval, the (optional) outer ring is
+/// divided into segments, e.g. one for each cycle of the workout. During a breathing exercise
+/// the inner ring is replaced by a circle that expands and contracts with the breath.
 pub struct Clock {
     link: ComponentLink<Self>,
     viewbox: (u64, u64, u64, u64),
@@ -11,23 +17,146 @@ pub struct Clock {
     position: (u64, u64),
     progress: f64,
     circumference: f64,
+    segments: Vec<Segment>,
+    outer_stroke_width: u64,
+    outer_radius: u64,
+    outer_circumference: f64,
     text: String,
+    label: String,
     color: &'static str,
     darken: bool,   // tells if the text color should be dark to highlight a difference between states
+    flash: bool,    // tells if the clock face should light up
+    breath: Option<Breath>,
+}
+
+/// A segment of the outer ring of a `Clock`.
+///
+/// * `progress` - How much of the segment is filled, a floating point value between 0 and 1.
+/// * `color` - The color of the filled part of the segment.
+/// * `active` - If set to true, the unfilled part of the segment is highlighted.
+#[derive(Clone, PartialEq)]
+pub struct Segment {
+    pub progress: f64,
+    pub color: &'static str,
+    pub active: bool,
 }
 
 /// When a new `Clock` component is created it gets passed the following properties by it's parent:
 ///
 /// * `progress` - The current progress, a floating point value between 0 and 1.
+/// * `segments` - The segments of the outer ring (no outer ring is drawn if empty).
 /// * `text` - The Text to display (usually a clock face).
+/// * `label` - A short caption above the text (e.g. the name of the current step).
 /// * `darken` - If set to true, the text is greyed out.
 /// * `color` - The color of the progress bar when filled.
+/// * `flash` - If set to true, the clock face lights up in `color`.
+/// * `breath` - The phase of the breath, if set the progress is shown by the breathing circle.
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub progress: f64,
+    #[prop_or_default]
+    pub segments: Vec<Segment>,
     pub text: String,
+    #[prop_or_default]
+    pub label: String,
     pub darken: bool,
     pub color: &'static str,
+    #[prop_or_default]
+    pub flash: bool,
+    #[prop_or_default]
+    pub breath: Option<Breath>,
+}
+
+impl Clock {
+    /// Render the progress of the current interval, as ring or as breathing circle.
+    fn view_progress(&self) -> Html {
+        let breath = match self.breath {
+            Some(breath) => breath,
+            None => {
+                // No css transition, the progress is updated on every animation frame.
+                let style = format!("stroke-dasharray: {} {}; stroke-dashoffset: {};
+                    transform: rotate(-90deg); transform-origin: 50% 50%;",
+                    self.circumference, self.circumference, self.circumference - self.progress * self.circumference);
+                return html! {
+                    <circle
+                        class="progress-ring__circle"
+                        stroke-width={ self.stroke_width }
+                        stroke={ self.color }
+                        fill="none"
+                        r={ self.radius }
+                        cx={ self.position.0 }
+                        cy={ self.position.1 }
+                        style={ style }
+                    />
+                };
+            },
+        };
+
+        // How far the lungs are filled, eased so the circle slows down at both ends.
+        let ease = |p: f64| (1.0 - (p * std::f64::consts::PI).cos()) / 2.0;
+        let fill = match breath {
+            Breath::Inhale => ease(self.progress),
+            Breath::HoldFull => 1.0,
+            Breath::Exhale => 1.0 - ease(self.progress),
+            Breath::HoldEmpty => 0.0,
+        };
+        let min = self.radius as f64 * 0.4;
+        let max = (self.radius + self.stroke_width / 2) as f64;
+
+        html! {
+            <circle
+                class="progress-ring__breath"
+                stroke="none"
+                fill={ self.color }
+                fill-opacity="0.6"
+                r={ min + (max - min) * fill }
+                cx={ self.position.0 }
+                cy={ self.position.1 }
+            />
+        }
+    }
+
+    /// Render the segments of the outer ring.
+    ///
+    /// Each segment is drawn as a dash of a circle, rotated so that the first segment starts at
+    /// the top.
+    fn view_segments(&self) -> Html {
+        if self.segments.is_empty() {
+            return html! {};
+        }
+
+        let length = self.outer_circumference / self.segments.len() as f64;
+        let gap = (length * 0.2).min(4.0);
+        let dash = |offset: f64, len: f64| format!("stroke-dasharray: {} {}; stroke-dashoffset: {};
+            transform: rotate(-90deg); transform-origin: 50% 50%;",
+            len, self.outer_circumference, -offset);
+
+        self.segments.iter().enumerate().map(|(i, segment)| {
+            let offset = i as f64 * length;
+            html! {
+                <>
+                    <circle
+                        stroke-width={ self.outer_stroke_width }
+                        stroke={ if segment.active { "#ffffff" } else { "#555555" } }
+                        fill="none"
+                        r={ self.outer_radius }
+                        cx={ self.position.0 }
+                        cy={ self.position.1 }
+                        style={ dash(offset, length - gap) }
+                    />
+                    <circle
+                        stroke-width={ self.outer_stroke_width }
+                        stroke={ segment.color }
+                        fill="none"
+                        r={ self.outer_radius }
+                        cx={ self.position.0 }
+                        cy={ self.position.1 }
+                        style={ dash(offset, (length - gap) * segment.progress) }
+                    />
+                </>
+            }
+        }).collect::<Html>()
+    }
 }
 
 impl Component for Clock {
@@ -39,6 +168,8 @@ impl Component for Clock {
         let height = 500;
         let stroke_width = 21;
         let radius = (width / 2) - (stroke_width * 2);
+        let outer_stroke_width = 12;
+        let outer_radius = (width / 2) - outer_stroke_width - 3;
 
         Self {
             link,
@@ -50,9 +181,16 @@ impl Component for Clock {
             position: (width / 2, height / 2),
             progress: props.progress,
             circumference: radius as f64 * 2.0 * std::f64::consts::PI,
+            segments: props.segments,
+            outer_stroke_width,
+            outer_radius,
+            outer_circumference: outer_radius as f64 * 2.0 * std::f64::consts::PI,
             text: props.text,
+            label: props.label,
             color: props.color,
             darken: props.darken,
+            flash: props.flash,
+            breath: props.breath,
         }
     }
 
@@ -62,15 +200,17 @@ impl Component for Clock {
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
         self.progress = props.progress;
+        self.segments = props.segments;
         self.text = props.text;
+        self.label = props.label;
         self.darken = props.darken;
+        self.color = props.color;
+        self.flash = props.flash;
+        self.breath = props.breath;
         true
     }
 
     fn view(&self) -> Html {
-        let style = format!("stroke-dasharray: {} {}; stroke-dashoffset: {};
-            transition: stroke-dashoffset 0.45s; transform: rotate(-90deg); transform-origin: 50% 50%;",
-            self.circumference, self.circumference, self.circumference - self.progress * self.circumference);
         html! {
             <svg
                 class="progress-ring"
@@ -85,21 +225,32 @@ impl Component for Clock {
                     cx={ self.position.0 }
                     cy={ self.position.1 }
                 />
+                { self.view_progress() }
+                { self.view_segments() }
                 <circle
-                    class="progress-ring__circle"
-                    stroke-width={ self.stroke_width }
-                    stroke={ self.color }
-                    fill="none"
-                    r={ self.radius }
+                    class="progress-ring__face"
+                    stroke="none"
+                    fill={ self.color }
+                    fill-opacity={ if self.flash { "0.4" } else { "0" } }
+                    r={ self.radius - self.stroke_width / 2 }
                     cx={ self.position.0 }
                     cy={ self.position.1 }
-                    style={ style }
                 />
+                <text
+                    x={ self.position.0 }
+                    y={ self.position.1 - 100 }
+                    text-anchor="middle"
+                    font-size="2.5em"
+                    fill={ if self.darken { "#808080" } else { "#ffffff" } }
+                    dominant-baseline="middle"
+                >
+                    { &self.label }
+                </text>
                 <text
                     x={ self.position.0 }
                     y={ self.position.1 }
                     text-anchor="middle"
-                    font-size="6em"
+                    font-size={ if self.text.len() > 8 { "5em" } else { "6em" } }
                     fill={ if self.darken { "#808080" } else { "#ffffff" } }
                     dominant-baseline="middle"
                 >
@@ -0,0 +1,219 @@
+use super::helper::{hours, minutes, seconds};
+
+/// The longest duration `parse` accepts, in seconds (99:59:59).
+pub const MAX: u64 = 99 * 3600 + 59 * 60 + 59;
+
+/// Parse a human-friendly duration into seconds.
+///
+/// Accepted inputs are plain seconds (`90`), clock notation (`1:30`, `1:02:00`) and numbers with
+/// units (`90s`, `2m15s`, `1h`, `1h 30m`). Returns `None` if the input isn't a valid duration or
+/// longer than `MAX`.
+pub fn parse(s: &str) -> Option<u64> {
+    let s = s.trim().to_lowercase();
+    let t = if s.is_empty() {
+        None
+    } else if s.contains(':') {
+        parse_clock(&s)
+    } else if s.chars().all(|c| c.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        parse_units(&s)
+    };
+    t.filter(|t| *t <= MAX)
+}
+
+/// Parse clock notation, i.e. `m:ss` or `h:mm:ss`.
+fn parse_clock(s: &str) -> Option<u64> {
+    let parts = s.split(':').map(|p| p.trim().parse::<u64>().ok()).collect::<Option<Vec<u64>>>()?;
+
+    match parts.as_slice() {
+        [m, s] if *s < 60 => m.checked_mul(60)?.checked_add(*s),
+        [h, m, s] if *m < 60 && *s < 60 => h.checked_mul(3600)?.checked_add(m * 60 + s),
+        _ => None,
+    }
+}
+
+/// Parse numbers with units (`h`, `m`/`min`, `s`/`sec`), e.g. `2m15s` or `1h 30m`.
+///
+/// Every unit may appear at most once and they have to be in descending order.
+fn parse_units(s: &str) -> Option<u64> {
+    let mut total: u64 = 0;
+    let mut last = u64::MAX; // factor of the last unit
+    let mut rest = s.trim_start();
+
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let value = rest[..digits].parse::<u64>().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let unit = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let factor = match &rest[..unit] {
+            "h" => 3600,
+            "m" | "min" => 60,
+            "s" | "sec" => 1,
+            _ => return None,
+        };
+        if factor >= last {
+            return None;
+        }
+        last = factor;
+
+        total = value.checked_mul(factor).and_then(|t| t.checked_add(total))?;
+        rest = rest[unit..].trim_start();
+    }
+
+    Some(total)
+}
+
+/// Format a duration given in seconds, as short as possible: `0:45`, `12:03` or `1:02:00`.
+pub fn format(t: u64) -> String {
+    if hours(t) > 0 {
+        format!("{}:{:02}:{:02}", hours(t), minutes(t), seconds(t))
+    } else {
+        format!("{}:{:02}", minutes(t), seconds(t))
+    }
+}
+
+/// Format a duration given in milliseconds like `format`, optionally with tenths of a second
+/// (`0:09.3`).
+///
+/// The duration is rounded up, to full seconds or to tenths respectively.
+pub fn format_ms(ms: f64, tenths: bool) -> String {
+    format_rounded(ms, tenths, f64::ceil)
+}
+
+/// Format an elapsed time given in milliseconds like `format_ms`, but rounded down, so a time
+/// counting up only shows what has actually passed.
+pub fn format_elapsed(ms: f64, tenths: bool) -> String {
+    format_rounded(ms, tenths, f64::floor)
+}
+
+/// Format milliseconds with the given rounding, to full seconds or to tenths.
+fn format_rounded(ms: f64, tenths: bool, round: fn(f64) -> f64) -> String {
+    let ms = ms.max(0.0);
+    if tenths {
+        let t = round(ms / 100.0) as u64;
+        format!("{}.{}", format(t / 10), t % 10)
+    } else {
+        format(round(ms / 1000.0) as u64)
+    }
+}
+
+/// Format a time span given in milliseconds as clock face.
+///
+/// During the final ten seconds tenths of a second are shown as well.
+pub fn clock_face(ms: f64) -> String {
+    format_ms(ms, ms <= 9900.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_valid() {
+        let cases = [
+            ("90", 90),
+            ("0", 0),
+            ("1:30", 90),
+            ("0:05", 5),
+            ("1:02:00", 3720),
+            ("90s", 90),
+            ("45 sec", 45),
+            ("2m15s", 135),
+            ("2min", 120),
+            ("1h", 3600),
+            ("1h 30m", 5400),
+            ("1H30M", 5400),
+            ("  1:30  ", 90),
+            ("99:59:59", MAX),
+            ("99h 59m 59s", MAX),
+        ];
+        for (input, expected) in cases.iter() {
+            assert_eq!(parse(input), Some(*expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let cases = [
+            "",
+            "   ",
+            "1m1h",
+            "1m1m",
+            "1:60",
+            "1:60:00",
+            "1:00:60",
+            "1:2:3:4",
+            ":30",
+            "1.5m",
+            "-5",
+            "5x",
+            "m",
+            "100:00:00",
+            "100h",
+            "360000",
+            "5124095576030431h",
+            "5124095576030432h",
+            "5124095576030432:00:00",
+            "307445734561825861:00",
+            "18446744073709551615h 1s",
+            "99999999999999999999",
+        ];
+        for input in cases.iter() {
+            assert_eq!(parse(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn format_seconds() {
+        let cases = [
+            (0, "0:00"),
+            (45, "0:45"),
+            (723, "12:03"),
+            (3599, "59:59"),
+            (3600, "1:00:00"),
+            (3720, "1:02:00"),
+        ];
+        for (t, expected) in cases.iter() {
+            assert_eq!(format(*t), *expected);
+        }
+    }
+
+    #[test]
+    fn format_milliseconds() {
+        assert_eq!(format_ms(0.0, false), "0:00");
+        assert_eq!(format_ms(1.0, false), "0:01");
+        assert_eq!(format_ms(59000.0, false), "0:59");
+        assert_eq!(format_ms(59001.0, false), "1:00");
+        assert_eq!(format_ms(-5.0, false), "0:00");
+        assert_eq!(format_ms(9300.0, true), "0:09.3");
+        assert_eq!(format_ms(9301.0, true), "0:09.4");
+        assert_eq!(format_ms(61000.0, true), "1:01.0");
+    }
+
+    #[test]
+    fn format_elapsed_rounds_down() {
+        assert_eq!(format_elapsed(0.0, true), "0:00.0");
+        assert_eq!(format_elapsed(1.0, true), "0:00.0");
+        assert_eq!(format_elapsed(99.0, true), "0:00.0");
+        assert_eq!(format_elapsed(100.0, true), "0:00.1");
+        assert_eq!(format_elapsed(61050.0, true), "1:01.0");
+        assert_eq!(format_elapsed(9999.0, false), "0:09");
+        assert_eq!(format_elapsed(60000.0, false), "1:00");
+        assert_eq!(format_elapsed(-5.0, false), "0:00");
+    }
+
+    #[test]
+    fn clock_face_tenths() {
+        assert_eq!(clock_face(10000.0), "0:10");
+        assert_eq!(clock_face(9901.0), "0:10");
+        assert_eq!(clock_face(9900.0), "0:09.9");
+        assert_eq!(clock_face(9899.0), "0:09.9");
+        assert_eq!(clock_face(50.0), "0:00.1");
+        assert_eq!(clock_face(0.0), "0:00.0");
+    }
+}
@@ -0,0 +1,974 @@
+use serde::{Deserialize, Serialize};
+
+use crate::program::{Kind, Program, Step};
+
+/// The different states of the `Engine`.
+///
+/// The `Engine` starts in `Idle` state. If the user clicks the start button the `Engine` switches
+/// to the `Start` state and a countdown appears after which it runs through the steps of the
+/// program. `Work` steps are executed in `On` state, `Rest` steps in `Off` state, until either all
+/// steps are completed or the user presses the pause button. An open step keeps the `Engine` in
+/// its state until it is stopped, a manual step until the user is done. In `Pause` state the
+/// timer can either be resumed or reset.
+///
+/// # States
+///
+/// * `Start` - Start/ Resume the timer.
+/// * `On` - The state in which the user is demanded to work out.
+/// * `Off` - The state in which the user is granted some rest.
+/// * `Waiting` - The user works out until they are done (manual step).
+/// * `Paused` - The timer is paused.
+/// * `Idle` - Do nothing.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum State {
+    Start,
+    On,
+    Off,
+    Waiting,
+    Paused,
+    Idle,
+}
+
+/// Events emitted by the `Engine` while it is ticking.
+///
+/// The `Engine` itself doesn't know anything about sounds or rendering, it's up to the driver
+/// (e.g. the `Timer` component) to react to the events.
+///
+/// # Events
+///
+/// * `Countdown` - A countdown beep should be played, carries the seconds left (`0` means the
+///   current phase is over or the target of an open step is reached).
+/// * `IntervalStarted` - A new step has started, carries the state (`On` or `Off`) it runs in.
+/// * `Cue` - One of the optional `Cues` is due.
+/// * `Finished` - All steps are completed.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Event {
+    Countdown(u64),
+    IntervalStarted(State),
+    Cue(Cue),
+    Finished,
+}
+
+/// Optional cues the `Engine` can emit in addition to the countdown beeps.
+///
+/// # Cues
+///
+/// * `Halfway` - Half of the current `Work` step is over.
+/// * `LastRound` - The last `Work` step of the program has started.
+/// * `OneMinuteLeft` - The current step is over in one minute.
+/// * `TenSecondsLeft` - The current step is over in ten seconds.
+/// * `Warning` - The current `Work` step is almost over (see `Cues::warning`).
+/// * `Bell` - Another interval of the current step has passed (see `Cues::bell`).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Cue {
+    Halfway,
+    LastRound,
+    OneMinuteLeft,
+    TenSecondsLeft,
+    Warning,
+    Bell,
+}
+
+/// Settings that tell the `Engine` when to emit which events.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cues {
+    pub lead_in: u64,       // seconds until the first step starts
+    pub beeps: u64,         // number of countdown beeps before a phase is over
+    pub halfway: bool,      // emit `Cue::Halfway`
+    pub last_round: bool,   // emit `Cue::LastRound`
+    pub one_minute: bool,   // emit `Cue::OneMinuteLeft`
+    pub ten_seconds: bool,  // emit `Cue::TenSecondsLeft`
+    pub warning: u64,       // seconds before the end of a `Work` step to emit `Cue::Warning` (0 for none)
+    pub bell: u64,          // emit `Cue::Bell` every `bell` seconds into a step (0 for none)
+}
+
+impl Default for Cues {
+    fn default() -> Self {
+        Self {
+            lead_in: 5,
+            beeps: 4,
+            halfway: false,
+            last_round: false,
+            one_minute: false,
+            ten_seconds: false,
+            warning: 0,
+            bell: 0,
+        }
+    }
+}
+
+/// A change made to a running workout by the user, see `Engine::edit`.
+///
+/// # Edits
+///
+/// * `Done` - Complete the current manual step.
+/// * `Skip` - Skip the rest of the current phase (lead-in or step).
+/// * `Back` - Go back to the previous step (restart the first one).
+/// * `Adjust` - Add (or subtract, if negative) seconds to the current phase.
+/// * `ExtraRound` - Repeat the last round of the workout once more.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Edit {
+    Done,
+    Skip,
+    Back,
+    Adjust(i64),
+    ExtraRound,
+}
+
+/// The time it took the user to complete a manual step.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Record {
+    pub name: String,   // name of the step
+    pub time: f64,      // milliseconds
+}
+
+/// The state machine of an interval timer, independent of any framework.
+///
+/// The `Engine` executes the steps of a `Program` one after another. It doesn't count ticks,
+/// instead it derives its state from the timestamps (in milliseconds, e.g. from
+/// `performance.now()`) passed to `start`, `stop` and `tick`. The time spent in `Paused` state
+/// is subtracted, so the state stays accurate no matter how irregular `tick` is called.
+pub struct Engine {
+    program: Vec<Step>,     // the steps of the configured program in order of execution
+    steps: Vec<Step>,       // the steps of the current run, including extra rounds
+    index: usize,           // index of the current step
+    cycles: u64,            // total number of `Work` steps
+    cues: Cues,             // when to emit which events
+    auto_start: bool,       // start the next step right away, otherwise pause in between
+    started_at: f64,        // timestamp the timer has been started at
+    paused_at: f64,         // timestamp the timer has been paused at
+    paused: f64,            // total milliseconds the timer has been paused
+    phase_start: f64,       // elapsed milliseconds at which the current phase (lead-in or step) started
+    extra: f64,             // milliseconds added to (or subtracted from) the current phase
+    elapsed: f64,           // elapsed milliseconds at the last tick
+    counter_c: u64,         // completed `Work` steps
+    work: Vec<f64>,         // milliseconds spent in each completed `Work` step
+    laps: Vec<f64>,         // split times (milliseconds into the step) taken during an open step
+    records: Vec<Record>,   // time taken for the completed manual steps
+    state: State,           // the current state of the timer
+    saved_state: State,     // Used to save the state if the timer is paused.
+}
+
+impl Engine {
+    /// Create a new `Engine` in `Idle` state.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The program to execute.
+    pub fn new(program: &Program) -> Self {
+        let mut engine = Self {
+            program: Vec::new(),
+            steps: Vec::new(),
+            index: 0,
+            cycles: 0,
+            cues: Cues::default(),
+            auto_start: true,
+            started_at: 0.0,
+            paused_at: 0.0,
+            paused: 0.0,
+            phase_start: 0.0,
+            extra: 0.0,
+            elapsed: 0.0,
+            counter_c: 0,
+            work: Vec::new(),
+            laps: Vec::new(),
+            records: Vec::new(),
+            state: State::Idle,
+            saved_state: State::Idle,
+        };
+        engine.configure(program);
+        engine.reset();
+        engine
+    }
+
+    /// Set a new program to execute.
+    ///
+    /// The new program takes effect after the next `reset`.
+    pub fn configure(&mut self, program: &Program) {
+        self.program = program.steps();
+    }
+
+    /// Set the cues to emit.
+    pub fn set_cues(&mut self, cues: Cues) {
+        self.cues = cues;
+    }
+
+    /// Set whether the next step starts right away when a step is over (the default). If not,
+    /// the engine pauses at the beginning of every step but the first until it is started again.
+    pub fn set_auto_start(&mut self, auto_start: bool) {
+        self.auto_start = auto_start;
+    }
+
+    /// Start the engine if it is `Idle` or resume it if it is `Paused`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn start(&mut self, now: f64) {
+        match self.state {
+            State::Idle => { // Start timer
+                if self.program.is_empty() {
+                    return;
+                }
+
+                self.reset();
+                self.started_at = now;
+                self.state = State::Start;
+            },
+            State::Paused => { // Resume timer
+                self.paused += now - self.paused_at;
+                self.state = self.saved_state;
+            },
+            _ => {},
+        }
+    }
+
+    /// Start the engine as if it had been started `elapsed` milliseconds (not counting pauses)
+    /// before `now`.
+    ///
+    /// Used to recover a workout, all events up to `now` are dropped. The engine is `Idle`
+    /// afterwards if the workout would already be completed. Manual steps are only completed by
+    /// the `Done` edits passed along.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - Milliseconds the workout has been running.
+    /// * `edits` - The edits made to the workout, with the milliseconds it had been running
+    ///   at the time.
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn restore(&mut self, elapsed: f64, edits: &[(f64, Edit)], now: f64) {
+        // The time spent waiting for the next step to be started isn't part of `elapsed`.
+        let auto_start = self.auto_start;
+        self.auto_start = true;
+
+        let started_at = now - elapsed;
+        self.reset();
+        self.start(started_at);
+        for (at, edit) in edits {
+            self.edit(*edit, started_at + at);
+        }
+        self.tick(now);
+
+        self.auto_start = auto_start;
+    }
+
+    /// Pause the engine (state is preserved until it is started again or reset).
+    ///
+    /// Call `tick` with the same timestamp right before, so the engine is paused at the exact
+    /// millisecond.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn stop(&mut self, now: f64) {
+        match self.state {
+            State::Idle | State::Paused => {},
+            _ => {
+                self.paused_at = now;
+                self.saved_state = self.state;  // Save current state
+                self.state = State::Paused;
+            },
+        }
+    }
+
+    /// Reset everything to the currently configured settings.
+    pub fn reset(&mut self) {
+        self.steps = self.program.clone(); // drop extra rounds
+        self.cycles = self.steps.iter().filter(|s| s.kind == Kind::Work).count() as u64;
+        self.index = 0;
+        self.paused = 0.0;
+        self.phase_start = 0.0;
+        self.extra = 0.0;
+        self.elapsed = 0.0;
+        self.counter_c = 0;
+        self.work.clear();
+        self.laps.clear();
+        self.records.clear();
+        self.state = State::Idle;
+        self.saved_state = State::Idle;
+    }
+
+    /// Bring the engine up to date with the given timestamp.
+    ///
+    /// Returns the events that occurred since the last tick, in order. If the last tick is
+    /// long ago, all step transitions in between are reported but countdown beeps and timed cues
+    /// only for the current phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn tick(&mut self, now: f64) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        match self.state {
+            State::Start | State::On | State::Off | State::Waiting => {},
+            State::Paused | State::Idle => return events,
+        }
+
+        // Timestamps from different sources (e.g. animation frames) may be slightly out of order.
+        let last = self.elapsed;
+        self.elapsed = (now - self.started_at - self.paused).max(last);
+
+        // Move on to the next phase as long as the current one is over.
+        loop {
+            let end = self.phase_end();
+            if self.elapsed < end {
+                break;
+            }
+
+            events.push(Event::Countdown(0));
+            if !self.next_phase(end, &mut events) {
+                return events;
+            }
+        }
+
+        // The end of the phase, or the target if the current step is open.
+        let end = self.phase_start + self.phase_duration();
+        let crossed = |at: f64| at > last.max(self.phase_start) && at <= self.elapsed;
+
+        if self.open_step() && crossed(end) { // Target reached
+            events.push(Event::Countdown(0));
+        }
+
+        // Timed cues crossed since the last tick.
+        if self.state != State::Start {
+            if self.cues.halfway && self.state == State::On && crossed((self.phase_start + end) / 2.0) {
+                events.push(Event::Cue(Cue::Halfway));
+            }
+
+            if self.cues.one_minute && crossed(end - 60000.0) {
+                events.push(Event::Cue(Cue::OneMinuteLeft));
+            }
+
+            if self.cues.ten_seconds && crossed(end - 10000.0) {
+                events.push(Event::Cue(Cue::TenSecondsLeft));
+            }
+
+            if self.cues.warning > 0 && self.state == State::On && crossed(end - (self.cues.warning * 1000) as f64) {
+                events.push(Event::Cue(Cue::Warning));
+            }
+
+            if self.cues.bell > 0 {
+                let bell = (self.cues.bell * 1000) as f64;
+                let at = self.phase_start + ((self.elapsed - self.phase_start) / bell).floor() * bell;
+                if at > self.phase_start && at < end && crossed(at) {
+                    events.push(Event::Cue(Cue::Bell));
+                }
+            }
+        }
+
+        // Countdown beeps for the seconds crossed since the last tick.
+        for left in (1..=self.cues.beeps).rev() {
+            if crossed(end - (left * 1000) as f64) {
+                events.push(Event::Countdown(left));
+            }
+        }
+
+        events
+    }
+
+    /// Make a change to the running workout.
+    ///
+    /// Returns the events that occurred, like `tick`. Nothing happens unless the engine is
+    /// running, time can only be adjusted if the current phase has a fixed length. Time taken
+    /// away from a phase ends it at the latest at `now`.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit` - The change to make.
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn edit(&mut self, edit: Edit, now: f64) -> Vec<Event> {
+        let mut events = self.tick(now);
+
+        match self.state {
+            State::Start | State::On | State::Off | State::Waiting => {},
+            State::Paused | State::Idle => return events,
+        }
+
+        match edit {
+            Edit::Done => {
+                if self.state != State::Waiting {
+                    return events;
+                }
+
+                let name = self.steps[self.index].name.clone();
+                self.records.push(Record { name, time: self.phase_elapsed() });
+
+                events.push(Event::Countdown(0));
+                self.next_phase(self.elapsed, &mut events);
+            },
+            Edit::Skip => {
+                events.push(Event::Countdown(0));
+                self.next_phase(self.elapsed, &mut events);
+            },
+            Edit::Back => {
+                if self.lead_in() {
+                    return events;
+                }
+
+                if self.index > 0 {
+                    self.index -= 1;
+                    if self.steps[self.index].kind == Kind::Work { // Completed again later
+                        self.counter_c = self.counter_c.saturating_sub(1);
+                        self.work.pop();
+                    }
+                    if self.steps[self.index].manual {
+                        self.records.pop();
+                    }
+                }
+                self.laps.clear();
+                self.phase_start = self.elapsed;
+                self.extra = 0.0;
+                self.state = self.step_state();
+                events.push(Event::IntervalStarted(self.state));
+            },
+            Edit::Adjust(secs) => {
+                if self.open_step() || self.manual_step() {
+                    return events;
+                }
+
+                let base = self.phase_duration() - self.extra;
+                self.extra = (self.extra + (secs * 1000) as f64).max(self.phase_elapsed() - base);
+            },
+            Edit::ExtraRound => {
+                let last = match self.steps.iter().rposition(|s| s.kind == Kind::Work) {
+                    Some(last) => last,
+                    None => return events,
+                };
+
+                // The last work step, together with the rest before it.
+                let mut round = vec![self.steps[last].clone()];
+                if last > 0 && self.steps[last - 1].kind == Kind::Rest {
+                    round.insert(0, self.steps[last - 1].clone());
+                }
+
+                let at = (last + 1).max(self.index + 1);
+                for (i, step) in round.into_iter().enumerate() {
+                    self.steps.insert(at + i, step);
+                }
+                self.cycles += 1;
+            },
+        }
+
+        events
+    }
+
+    /// Move on from the current phase, which is over at `end` (elapsed milliseconds), to the
+    /// next one.
+    ///
+    /// Returns `false` if the engine doesn't go on by itself, i.e. all steps are completed or it
+    /// waits for the user to start the next step.
+    fn next_phase(&mut self, end: f64, events: &mut Vec<Event>) -> bool {
+        let lead_in = self.state == State::Start;
+
+        match self.state {
+            State::Start => { // The lead-in is over, switch to the first step.
+                self.index = 0;
+            },
+            _ => {
+                if self.state == State::On || self.state == State::Waiting { // `Work` step completed.
+                    self.counter_c += 1;
+                    self.work.push(end - self.phase_start);
+                }
+
+                if self.index + 1 < self.steps.len() { // Not all steps are completed.
+                    self.index += 1;
+                } else { // All steps completed, Nice Job !
+                    self.state = State::Idle;
+                    events.push(Event::Finished);
+                    return false;
+                }
+            },
+        }
+
+        self.phase_start = end;
+        self.extra = 0.0;
+        self.state = self.step_state();
+        events.push(Event::IntervalStarted(self.state));
+
+        if self.cues.last_round && self.state != State::Off
+            && self.cycles > 1 && self.counter_c + 1 == self.cycles {
+            events.push(Event::Cue(Cue::LastRound));
+        }
+
+        if !self.auto_start && !lead_in { // Wait for the user to start the next step.
+            self.elapsed = end;
+            self.paused_at = self.started_at + self.paused + end;
+            self.saved_state = self.state;
+            self.state = State::Paused;
+            return false;
+        }
+
+        true
+    }
+
+    /// True if the engine is (or has been paused) in the lead-in.
+    fn lead_in(&self) -> bool {
+        self.state == State::Start || (self.state == State::Paused && self.saved_state == State::Start)
+    }
+
+    /// True if the current phase is an open step.
+    fn open_step(&self) -> bool {
+        !self.lead_in() && self.steps.get(self.index).is_some_and(|s| s.open)
+    }
+
+    /// The duration (or target, if the step is open) of the current phase (lead-in or step) in
+    /// milliseconds, including the time added by the user.
+    fn phase_duration(&self) -> f64 {
+        let secs = if self.lead_in() {
+            self.cues.lead_in
+        } else {
+            self.steps.get(self.index).map_or(0, |s| s.duration)
+        };
+        (secs * 1000) as f64 + self.extra
+    }
+
+    /// True if the current phase is a manual step.
+    fn manual_step(&self) -> bool {
+        !self.lead_in() && self.steps.get(self.index).is_some_and(|s| s.manual)
+    }
+
+    /// Elapsed milliseconds at which the current phase is over.
+    fn phase_end(&self) -> f64 {
+        if self.open_step() || self.manual_step() {
+            f64::INFINITY
+        } else {
+            self.phase_start + self.phase_duration()
+        }
+    }
+
+    /// The state the current step is executed in.
+    fn step_state(&self) -> State {
+        match self.steps[self.index].kind {
+            Kind::Work if self.steps[self.index].manual => State::Waiting,
+            Kind::Work => State::On,
+            Kind::Rest => State::Off,
+        }
+    }
+
+    /// The current step, `None` if the program is empty.
+    pub fn step(&self) -> Option<&Step> {
+        self.steps.get(self.index)
+    }
+
+    /// The timestamp the engine has been paused at, only meaningful in `Paused` state.
+    ///
+    /// If the engine paused by itself between two steps, it's the moment the step was over.
+    pub fn paused_at(&self) -> f64 {
+        self.paused_at
+    }
+
+    /// The current state of the engine.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Milliseconds left of the current phase (lead-in or step), or until the target of an open
+    /// step is reached.
+    pub fn remaining_ms(&self) -> f64 {
+        (self.phase_start + self.phase_duration() - self.elapsed).max(0.0)
+    }
+
+    /// Milliseconds spent in the current phase (lead-in or step).
+    pub fn phase_elapsed(&self) -> f64 {
+        self.elapsed - self.phase_start
+    }
+
+    /// Take a split time if the engine is running an open step.
+    ///
+    /// Call `tick` right before, so the split time is accurate.
+    pub fn lap(&mut self) {
+        if (self.state == State::On || self.state == State::Off) && self.open_step() {
+            self.laps.push(self.phase_elapsed());
+        }
+    }
+
+    /// The steps that haven't been started yet, all of them if the engine is `Idle`.
+    pub fn upcoming(&self) -> &[Step] {
+        match self.state {
+            State::Idle => &self.steps,
+            _ if self.lead_in() => &self.steps[self.index..],
+            _ => &self.steps[self.index + 1..],
+        }
+    }
+
+    /// The split times (milliseconds into the step) taken during the current open step.
+    pub fn laps(&self) -> &[f64] {
+        &self.laps
+    }
+
+    /// The milliseconds spent in each completed `Work` step so far, in order. Time added or
+    /// taken away and skipped steps are taken into account.
+    pub fn work(&self) -> &[f64] {
+        &self.work
+    }
+
+    /// The time it took to complete each manual step so far, in order.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Seconds left of the current phase (lead-in or step), rounded up.
+    pub fn remaining(&self) -> u64 {
+        (self.remaining_ms() / 1000.0).ceil() as u64
+    }
+
+    /// The progress through the current phase, a floating point value between 0 and 1.
+    pub fn phase_progress(&self) -> f64 {
+        let duration = self.phase_duration();
+        if duration <= 0.0 {
+            0.0
+        } else {
+            (1.0 - self.remaining_ms() / duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Number of completed `Work` steps.
+    pub fn completed(&self) -> u64 {
+        self.counter_c
+    }
+
+    /// Total number of `Work` steps.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// The progress of the current cycle, i.e. the progress through the current `Work` step
+    /// (`0` if the current step is no `Work` step).
+    pub fn cycle_progress(&self) -> f64 {
+        let working = self.state == State::On
+            || (self.state == State::Paused && self.saved_state == State::On);
+        if working { self.phase_progress() } else { 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::Block;
+
+    /// An engine with the default cues (5 seconds lead-in, 4 beeps) executing the given steps.
+    fn engine(steps: Vec<Step>) -> Engine {
+        Engine::new(&Program { blocks: steps.into_iter().map(Block::Step).collect() })
+    }
+
+    /// Two rounds of 10 seconds work with 5 seconds rest in between.
+    fn intervals() -> Engine {
+        engine(vec![Step::work("Work", 10), Step::rest("Rest", 5), Step::work("Work", 10)])
+    }
+
+    #[test]
+    fn runs_through_all_steps() {
+        let mut engine = intervals();
+        assert_eq!(engine.state(), State::Idle);
+
+        engine.start(0.0);
+        assert_eq!(engine.state(), State::Start);
+
+        assert_eq!(engine.tick(5000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.state(), State::On);
+        assert_eq!(engine.remaining(), 10);
+
+        assert_eq!(engine.tick(15000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+        assert_eq!(engine.state(), State::Off);
+        assert_eq!(engine.completed(), 1);
+
+        assert_eq!(engine.tick(20000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.state(), State::On);
+
+        assert_eq!(engine.tick(30000.0), vec![Event::Countdown(0), Event::Finished]);
+        assert_eq!(engine.state(), State::Idle);
+        assert_eq!(engine.completed(), 2);
+        assert_eq!(engine.work(), &[10000.0, 10000.0]);
+
+        assert!(engine.tick(40000.0).is_empty());
+    }
+
+    #[test]
+    fn lead_in_beeps() {
+        let mut engine = intervals();
+        engine.start(1000.0);
+
+        assert!(engine.tick(1500.0).is_empty());
+        for left in (1..=4).rev() {
+            assert_eq!(engine.tick((6000 - left * 1000) as f64), vec![Event::Countdown(left)]);
+        }
+        assert_eq!(engine.tick(6000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+    }
+
+    #[test]
+    fn configurable_lead_in_and_beeps() {
+        let mut engine = intervals();
+        engine.set_cues(Cues { lead_in: 3, beeps: 2, ..Cues::default() });
+        engine.start(0.0);
+
+        assert!(engine.tick(500.0).is_empty());
+        assert_eq!(engine.tick(1000.0), vec![Event::Countdown(2)]);
+        assert_eq!(engine.tick(2000.0), vec![Event::Countdown(1)]);
+        assert_eq!(engine.tick(3000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+
+        let mut engine = intervals();
+        engine.set_cues(Cues { lead_in: 0, beeps: 0, ..Cues::default() });
+        engine.start(0.0);
+        assert_eq!(engine.tick(0.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert!(engine.tick(9000.0).is_empty());
+    }
+
+    #[test]
+    fn timed_cues() {
+        let mut engine = engine(vec![Step::work("Work", 120), Step::rest("Rest", 30), Step::work("Work", 120)]);
+        engine.set_cues(Cues {
+            lead_in: 0,
+            beeps: 0,
+            halfway: true,
+            last_round: true,
+            one_minute: true,
+            ten_seconds: true,
+            warning: 5,
+            bell: 0,
+        });
+        engine.start(0.0);
+
+        assert_eq!(engine.tick(0.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.tick(60000.0), vec![Event::Cue(Cue::Halfway), Event::Cue(Cue::OneMinuteLeft)]);
+        assert_eq!(engine.tick(110000.0), vec![Event::Cue(Cue::TenSecondsLeft)]);
+        assert_eq!(engine.tick(115000.0), vec![Event::Cue(Cue::Warning)]);
+        assert_eq!(engine.tick(120000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+
+        // Halfway and warning only apply to `Work` steps.
+        assert_eq!(engine.tick(140000.0), vec![Event::Cue(Cue::TenSecondsLeft)]);
+        assert_eq!(engine.tick(150000.0), vec![
+            Event::Countdown(0),
+            Event::IntervalStarted(State::On),
+            Event::Cue(Cue::LastRound),
+        ]);
+    }
+
+    #[test]
+    fn bell() {
+        let mut engine = engine(vec![Step::work("Meditation", 600)]);
+        engine.set_cues(Cues { lead_in: 0, beeps: 0, bell: 120, ..Cues::default() });
+        engine.start(0.0);
+        engine.tick(0.0);
+
+        assert!(engine.tick(119000.0).is_empty());
+        assert_eq!(engine.tick(130000.0), vec![Event::Cue(Cue::Bell)]);
+        assert_eq!(engine.tick(500000.0), vec![Event::Cue(Cue::Bell)]); // only the latest one
+        assert_eq!(engine.tick(600000.0), vec![Event::Countdown(0), Event::Finished]); // none at the end
+    }
+
+    #[test]
+    fn pause_between_steps() {
+        let mut engine = intervals();
+        engine.set_auto_start(false);
+        engine.start(0.0);
+
+        // The first step starts right after the lead-in.
+        assert_eq!(engine.tick(5000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.state(), State::On);
+
+        // Late ticks pause at the end of the step.
+        assert_eq!(engine.tick(18000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+        assert_eq!(engine.state(), State::Paused);
+        assert_eq!(engine.paused_at(), 15000.0);
+        assert_eq!(engine.remaining_ms(), 5000.0);
+        assert!(engine.tick(40000.0).is_empty());
+
+        engine.start(40000.0);
+        assert_eq!(engine.state(), State::Off);
+        assert_eq!(engine.tick(45000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.state(), State::Paused);
+    }
+
+    #[test]
+    fn pauses_are_not_counted() {
+        let mut engine = intervals();
+        engine.start(0.0);
+        engine.tick(7000.0);
+        engine.stop(7000.0);
+        assert_eq!(engine.state(), State::Paused);
+        assert_eq!(engine.remaining_ms(), 8000.0);
+
+        assert!(engine.tick(60000.0).is_empty());
+        assert_eq!(engine.remaining_ms(), 8000.0);
+
+        engine.start(50000.0);
+        assert_eq!(engine.state(), State::On);
+        assert!(engine.tick(50000.0).is_empty());
+        assert_eq!(engine.remaining_ms(), 8000.0);
+
+        let beeps = (1..=4).rev().map(Event::Countdown).collect::<Vec<Event>>();
+        assert_eq!(engine.tick(57000.0), beeps);
+        assert_eq!(engine.tick(58000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+        assert_eq!(engine.work(), &[10000.0]);
+    }
+
+    #[test]
+    fn empty_program() {
+        let mut engine = engine(Vec::new());
+        engine.start(0.0);
+        assert_eq!(engine.state(), State::Idle);
+        assert!(engine.tick(10000.0).is_empty());
+        assert_eq!(engine.step(), None);
+        assert_eq!(engine.cycles(), 0);
+    }
+
+    #[test]
+    fn late_tick_reports_every_transition() {
+        let mut engine = intervals();
+        engine.start(0.0);
+        engine.tick(4500.0);
+
+        // Beeps of phases that are already over are dropped.
+        assert_eq!(engine.tick(100000.0), vec![
+            Event::Countdown(0),
+            Event::IntervalStarted(State::On),
+            Event::Countdown(0),
+            Event::IntervalStarted(State::Off),
+            Event::Countdown(0),
+            Event::IntervalStarted(State::On),
+            Event::Countdown(0),
+            Event::Finished,
+        ]);
+        assert_eq!(engine.completed(), 2);
+        assert_eq!(engine.work(), &[10000.0, 10000.0]);
+    }
+
+    #[test]
+    fn late_tick_beeps_for_the_current_phase() {
+        let mut engine = intervals();
+        engine.start(0.0);
+
+        assert_eq!(engine.tick(13500.0), vec![
+            Event::Countdown(0),
+            Event::IntervalStarted(State::On),
+            Event::Countdown(4),
+            Event::Countdown(3),
+            Event::Countdown(2),
+        ]);
+        assert_eq!(engine.remaining_ms(), 1500.0);
+    }
+
+    /// The names of the steps that haven't been started yet.
+    fn upcoming(engine: &Engine) -> Vec<&str> {
+        engine.upcoming().iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn skip() {
+        let mut engine = intervals();
+        engine.start(0.0);
+
+        assert_eq!(engine.edit(Edit::Skip, 500.0), vec![Event::Countdown(0), Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.remaining_ms(), 10000.0);
+
+        assert_eq!(engine.edit(Edit::Skip, 3000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+        assert_eq!(engine.completed(), 1);
+        assert_eq!(engine.work(), &[2500.0]);
+    }
+
+    #[test]
+    fn back_reverts_the_completed_step() {
+        let mut engine = intervals();
+        engine.start(0.0);
+        engine.tick(15500.0);
+        assert_eq!(engine.completed(), 1);
+
+        assert_eq!(engine.edit(Edit::Back, 15500.0), vec![Event::IntervalStarted(State::On)]);
+        assert_eq!(engine.state(), State::On);
+        assert_eq!(engine.completed(), 0);
+        assert!(engine.work().is_empty());
+        assert_eq!(engine.remaining_ms(), 10000.0);
+
+        assert_eq!(engine.tick(25500.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+        assert_eq!(engine.completed(), 1);
+        assert_eq!(engine.work(), &[10000.0]);
+    }
+
+    #[test]
+    fn adjust_ends_the_phase_now_at_the_latest() {
+        let mut engine = intervals();
+        engine.start(0.0);
+        engine.tick(5000.0);
+
+        assert!(engine.edit(Edit::Adjust(10), 6000.0).is_empty());
+        assert_eq!(engine.remaining_ms(), 19000.0);
+
+        assert!(engine.edit(Edit::Adjust(-60), 8000.0).is_empty());
+        assert_eq!(engine.remaining_ms(), 0.0);
+        assert_eq!(engine.tick(8000.0), vec![Event::Countdown(0), Event::IntervalStarted(State::Off)]);
+        assert_eq!(engine.work(), &[3000.0]);
+    }
+
+    #[test]
+    fn extra_round_before_the_cool_down() {
+        let mut engine = engine(vec![
+            Step::work("Work", 10),
+            Step::rest("Rest", 5),
+            Step::work("Work", 10),
+            Step::rest("Cool-down", 30),
+        ]);
+        engine.start(0.0);
+        engine.edit(Edit::ExtraRound, 1000.0);
+
+        assert_eq!(engine.cycles(), 3);
+        assert_eq!(upcoming(&engine), vec!["Work", "Rest", "Work", "Rest", "Work", "Cool-down"]);
+
+        let events = engine.tick(100000.0);
+        assert_eq!(events.last(), Some(&Event::Finished));
+        assert_eq!(engine.completed(), 3);
+    }
+
+    #[test]
+    fn reset_drops_extra_rounds() {
+        let mut engine = intervals();
+        engine.start(0.0);
+        engine.edit(Edit::ExtraRound, 1000.0);
+        assert_eq!(engine.cycles(), 3);
+
+        engine.reset();
+        assert_eq!(engine.cycles(), 2);
+        assert_eq!(upcoming(&engine), vec!["Work", "Rest", "Work"]);
+
+        engine.start(2000.0);
+        engine.tick(100000.0);
+        assert_eq!(engine.completed(), 2);
+    }
+
+    #[test]
+    fn restore_replays_edits() {
+        let steps = vec![Step::manual("Push-ups"), Step::rest("Rest", 30), Step::work("Plank", 10)];
+        let mut engine = engine(steps.clone());
+        engine.set_auto_start(false);
+        engine.restore(20000.0, &[(12000.0, Edit::Done)], 100000.0);
+
+        assert_eq!(engine.records(), &[Record { name: "Push-ups".to_string(), time: 7000.0 }]);
+        assert_eq!(engine.step().map(|s| s.name.as_str()), Some("Rest"));
+        assert_eq!(engine.state(), State::Off);
+        assert_eq!(engine.remaining_ms(), 22000.0);
+        assert_eq!(engine.completed(), 1);
+
+        // Without the edit the manual step is still running.
+        let mut engine = self::engine(steps);
+        engine.restore(20000.0, &[], 100000.0);
+        assert!(engine.records().is_empty());
+        assert_eq!(engine.step().map(|s| s.name.as_str()), Some("Push-ups"));
+        assert_eq!(engine.state(), State::Waiting);
+    }
+
+    #[test]
+    fn upcoming_steps() {
+        let mut engine = engine(vec![Step::work("A", 10), Step::work("B", 10)]);
+        assert_eq!(upcoming(&engine), vec!["A", "B"]);
+
+        engine.start(0.0);
+        engine.stop(1000.0);
+        assert_eq!(upcoming(&engine), vec!["A", "B"]); // paused during the lead-in
+
+        engine.start(2000.0);
+        engine.tick(7000.0);
+        engine.stop(7000.0);
+        assert_eq!(upcoming(&engine), vec!["B"]);
+    }
+}
@@ -1,28 +1,724 @@
 use yew::prelude::*;
 use super::helper::*;
+use super::duration;
+use super::program::{Breath, Breathing, Entry, Increment, Shape, Stretch};
+use super::settings::{Mode, Preset, Settings};
+use super::share;
 
 pub struct Form {
     link: ComponentLink<Self>,
-    on: u64,
-    off: u64,
-    cycles: u64,
-    callback: Callback<(u64, u64, u64)>,
+    settings: Settings,
+    presets: Vec<Preset>,   // the preset library
+    preset_name: String,    // content of the preset name input
+    callback: Callback<Settings>,
     text: &'static str,
 }
 
+/// The durations that can be changed through the form.
+#[derive(Copy, Clone)]
+pub enum Field {
+    On,
+    Off,
+    WarmUp,
+    SetRest,
+    CoolDown,
+    Target,
+    Countdown,
+    Period,
+    Focus,
+    ShortBreak,
+    LongBreak,
+    Round,
+    RoundRest,
+    Meditation,
+    Bell,
+    Switch,
+    StretchRest,
+    LadderLow,
+    LadderHigh,
+    LadderIncrement,
+    StationWork,
+    StationRest,
+    CircuitRest,
+    TurnWork,
+    TurnRest,
+    Bank,
+    Bonus,
+}
+
+impl Field {
+    /// Get a mutable reference to the duration (in seconds) this field represents in `settings`.
+    fn of(self, settings: &mut Settings) -> &mut u64 {
+        match self {
+            Field::On => &mut settings.workout.on,
+            Field::Off => &mut settings.workout.off,
+            Field::WarmUp => &mut settings.workout.warm_up,
+            Field::SetRest => &mut settings.workout.set_rest,
+            Field::CoolDown => &mut settings.workout.cool_down,
+            Field::Target => &mut settings.stopwatch.target,
+            Field::Countdown => &mut settings.countdown.duration,
+            Field::Period => &mut settings.emom.period,
+            Field::Focus => &mut settings.pomodoro.focus,
+            Field::ShortBreak => &mut settings.pomodoro.short_break,
+            Field::LongBreak => &mut settings.pomodoro.long_break,
+            Field::Round => &mut settings.boxing.round,
+            Field::RoundRest => &mut settings.boxing.rest,
+            Field::Meditation => &mut settings.meditation.duration,
+            Field::Bell => &mut settings.meditation.bell,
+            Field::Switch => &mut settings.stretching.switch,
+            Field::StretchRest => &mut settings.stretching.rest,
+            Field::LadderLow => &mut settings.ladder.low,
+            Field::LadderHigh => &mut settings.ladder.high,
+            Field::LadderIncrement => &mut settings.ladder.increment,
+            Field::StationWork => &mut settings.circuit.work,
+            Field::StationRest => &mut settings.circuit.rest,
+            Field::CircuitRest => &mut settings.circuit.round_rest,
+            Field::TurnWork => &mut settings.partner.work,
+            Field::TurnRest => &mut settings.partner.rest,
+            Field::Bank => &mut settings.chess.bank,
+            Field::Bonus => &mut settings.chess.bonus,
+        }
+    }
+}
+
+/// The lists of names that can be changed through the form.
+#[derive(Copy, Clone)]
+pub enum List {
+    Stations,
+    Participants,
+    Players,
+}
+
 pub enum Msg {
-    UpdateOnH(String),
-    UpdateOnM(String),
-    UpdateOnS(String),
-    UpdateOffH(String),
-    UpdateOffM(String),
-    UpdateOffS(String),
+    UpdateMode(i32),
+    UpdateHours(Field, String),
+    UpdateMinutes(Field, String),
+    UpdateSeconds(Field, String),
+    ParseDuration(Field, String),
     UpdateCycles(String),
+    UpdateSets(String),
+    UpdateRounds(String),
+    UpdateLabels(String),
+    UpdateSessions(String),
+    ToggleAutoStart,
+    UpdateBoxingRounds(String),
+    UpdateWarning(String),
+    UpdateBreath(Breath, String),
+    UpdateBreaths(String),
+    SetPattern([u64; 4]),
+    ToggleHideDigits,
+    AddStretch,
+    RemoveStretch(usize),
+    UpdateStretchName(usize, String),
+    UpdateStretchHold(usize, String),
+    ToggleBilateral(usize),
+    UpdateShape(i32),
+    UpdateLadderRest(String),
+    ToggleProportional,
+    AddEntry,
+    RemoveEntry(usize),
+    UpdateEntryName(usize, String),
+    UpdateEntryDuration(usize, String),
+    ToggleManual(usize),
+    ToggleRest(usize),
+    AddName(List),
+    RemoveName(List, usize),
+    UpdateName(List, usize, String),
+    UpdateCircuitRounds(String),
+    UpdateTurns(String),
+    UpdateIncrement(i32),
+    UpdateLeadIn(String),
+    UpdateBeeps(String),
+    ToggleHalfway,
+    ToggleLastRound,
+    ToggleOneMinute,
+    ToggleTenSeconds,
+    ToggleAlarm,
+    UpdatePresetName(String),
+    SavePreset,
+    LoadPreset(usize),
+    RenamePreset(usize),
+    DeletePreset(usize),
+    CopyShareLink,
+    LinkCopied(bool),
 }
 
+/// When a new `Form` component is created it gets passed the following properties by it's parent:
+///
+/// * `settings` - The current settings.
+/// * `callback` - Invoked with the new settings whenever the user changes something.
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
-    pub callback: Callback<(u64, u64, u64)>,
+    pub settings: Settings,
+    pub callback: Callback<Settings>,
+}
+
+impl Form {
+    /// The duration (in seconds) represented by `field`.
+    fn value(&self, field: Field) -> u64 {
+        *field.of(&mut self.settings.clone())
+    }
+
+    /// Get a mutable reference to the list of names represented by `list`.
+    fn names(&mut self, list: List) -> &mut Vec<String> {
+        match list {
+            List::Stations => &mut self.settings.circuit.stations,
+            List::Participants => &mut self.settings.partner.participants,
+            List::Players => &mut self.settings.chess.players,
+        }
+    }
+
+    /// Render a text input as well as the hour, minute and second sliders of a duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The duration to render.
+    /// * `id` - Prefix of the html ids of the sliders.
+    /// * `title` - The heading above the sliders.
+    fn view_duration(&self, field: Field, id: &str, title: &str) -> Html {
+        let t = self.value(field);
+
+        html! {
+            <>
+                <h3 class="center"><strong>{ title }</strong></h3>
+                <input type="text" class="form-control form-control-sm text-center mb-2" id={ format!("{}Text", id) }
+                    value={ duration::format(t) } title="e.g. 1:30, 90s, 2m15s or 1h"
+                    onchange={ self.link.callback(move |e: ChangeData| match e {
+                        ChangeData::Value(v) => Msg::ParseDuration(field, v),
+                        _ => Msg::ParseDuration(field, String::new()),
+                    }) }
+                />
+                <label for={ format!("{}Hour", id) }>{ format!("Hours: {}", hours(t)) }</label>
+                <input type="range" min="0" max="23", value={ hours(t) } class="custom-range" id={ format!("{}Hour", id) }
+                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateHours(field, e.value)) }
+                />
+                <label for={ format!("{}Minute", id) }>{ format!("Minutes: {}", minutes(t)) }</label>
+                <input type="range" min="0" max="59", value={ minutes(t) } class="custom-range" id={ format!("{}Minute", id) }
+                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateMinutes(field, e.value)) }
+                />
+                <label for={ format!("{}Second", id) }>{ format!("Seconds: {}", seconds(t)) }</label>
+                <input type="range" min="0" max="59", value={ seconds(t) } class="custom-range" id={ format!("{}Second", id) }
+                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateSeconds(field, e.value)) }
+                />
+            </>
+        }
+    }
+
+    /// Render the settings of the interval mode.
+    fn view_interval(&self) -> Html {
+        html! {
+            <>
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::On, "on", "On Time") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::Off, "off", "Off Time") }
+                    </div>
+                    <div class="col-sm-4">
+                        <h3 class="center"><strong>{"Cycles"}</strong></h3>
+                        <label for="cycles">{ format!("{}", self.settings.workout.cycles) }</label>
+                        <input type="range" min="1" max="100", value={ self.settings.workout.cycles } class="custom-range" id="cycles"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateCycles(e.value)) }
+                        />
+                    </div>
+                </div>
+                <hr/>
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::WarmUp, "warmUp", "Warm-up") }
+                    </div>
+                    <div class="col-sm-4">
+                        <h3 class="center"><strong>{"Sets"}</strong></h3>
+                        <label for="sets">{ format!("{}", self.settings.workout.sets) }</label>
+                        <input type="range" min="1" max="20", value={ self.settings.workout.sets } class="custom-range" id="sets"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateSets(e.value)) }
+                        />
+                        { self.view_duration(Field::SetRest, "setRest", "Set Rest") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::CoolDown, "coolDown", "Cool-down") }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the settings of the stopwatch mode.
+    fn view_stopwatch(&self) -> Html {
+        html! {
+            <div class="form-row">
+                <div class="col-sm-4 offset-sm-4">
+                    { self.view_duration(Field::Target, "target", "Target") }
+                </div>
+            </div>
+        }
+    }
+
+    /// Render the settings of the countdown mode.
+    fn view_countdown(&self) -> Html {
+        html! {
+            <div class="form-row">
+                <div class="col-sm-4 offset-sm-4">
+                    { self.view_duration(Field::Countdown, "countdown", "Duration") }
+                    <div class="custom-control custom-checkbox">
+                        <input type="checkbox" class="custom-control-input" id="alarm" checked={ self.settings.countdown.alarm }
+                            onclick={ self.link.callback(|_| Msg::ToggleAlarm) }
+                        />
+                        <label class="custom-control-label" for="alarm">{ "Alarm when the time is up" }</label>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Render the settings of the EMOM mode.
+    fn view_emom(&self) -> Html {
+        html! {
+            <div class="form-row">
+                <div class="col-sm-4">
+                    { self.view_duration(Field::Period, "period", "Period") }
+                </div>
+                <div class="col-sm-4">
+                    <h3 class="center"><strong>{"Rounds"}</strong></h3>
+                    <label for="rounds">{ format!("{}", self.settings.emom.rounds) }</label>
+                    <input type="range" min="1" max="60", value={ self.settings.emom.rounds } class="custom-range" id="rounds"
+                        oninput={ self.link.callback(|e: InputData| Msg::UpdateRounds(e.value)) }
+                    />
+                </div>
+                <div class="col-sm-4">
+                    <h3 class="center"><strong>{"Exercises"}</strong></h3>
+                    <input type="text" class="form-control form-control-sm text-center" id="labels"
+                        value={ self.settings.emom.labels.join(", ") } placeholder="e.g. A, B, C"
+                        onchange={ self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Value(v) => Msg::UpdateLabels(v),
+                            _ => Msg::UpdateLabels(String::new()),
+                        }) }
+                    />
+                </div>
+            </div>
+        }
+    }
+
+    /// Render the settings of the Pomodoro mode.
+    fn view_pomodoro(&self) -> Html {
+        html! {
+            <>
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::Focus, "focus", "Focus") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::ShortBreak, "shortBreak", "Short Break") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::LongBreak, "longBreak", "Long Break") }
+                    </div>
+                </div>
+                <div class="form-row">
+                    <div class="col-sm-4 offset-sm-4">
+                        <label for="sessions">{ format!("Long break after {} sessions", self.settings.pomodoro.sessions) }</label>
+                        <input type="range" min="1" max="10", value={ self.settings.pomodoro.sessions } class="custom-range" id="sessions"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateSessions(e.value)) }
+                        />
+                        <div class="custom-control custom-checkbox">
+                            <input type="checkbox" class="custom-control-input" id="autoStart" checked={ self.settings.pomodoro.auto_start }
+                                onclick={ self.link.callback(|_| Msg::ToggleAutoStart) }
+                            />
+                            <label class="custom-control-label" for="autoStart">{ "Start the next session automatically" }</label>
+                        </div>
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the settings of the boxing mode.
+    fn view_boxing(&self) -> Html {
+        html! {
+            <div class="form-row">
+                <div class="col-sm-4">
+                    { self.view_duration(Field::Round, "round", "Round") }
+                </div>
+                <div class="col-sm-4">
+                    { self.view_duration(Field::RoundRest, "roundRest", "Rest") }
+                </div>
+                <div class="col-sm-4">
+                    <h3 class="center"><strong>{"Rounds"}</strong></h3>
+                    <label for="boxingRounds">{ format!("{}", self.settings.boxing.rounds) }</label>
+                    <input type="range" min="1" max="30", value={ self.settings.boxing.rounds } class="custom-range" id="boxingRounds"
+                        oninput={ self.link.callback(|e: InputData| Msg::UpdateBoxingRounds(e.value)) }
+                    />
+                    <h3 class="center"><strong>{"Clapper"}</strong></h3>
+                    <label for="warning">{ format!("Seconds left: {}", self.settings.boxing.warning) }</label>
+                    <input type="range" min="0" max="30", value={ self.settings.boxing.warning } class="custom-range" id="warning"
+                        oninput={ self.link.callback(|e: InputData| Msg::UpdateWarning(e.value)) }
+                    />
+                </div>
+            </div>
+        }
+    }
+
+    /// Render the settings of the breathing mode.
+    fn view_breathing(&self) -> Html {
+        let breathing = &self.settings.breathing;
+        let phases = [
+            (Breath::Inhale, "inhale", "Inhale", breathing.inhale, 1),
+            (Breath::HoldFull, "holdFull", "Hold", breathing.hold_full, 0),
+            (Breath::Exhale, "exhale", "Exhale", breathing.exhale, 1),
+            (Breath::HoldEmpty, "holdEmpty", "Hold", breathing.hold_empty, 0),
+        ];
+
+        html! {
+            <>
+                <div class="btn-group mb-3" role="group">
+                    <button type="button" class="btn btn-outline-info" onclick={ self.link.callback(|_| Msg::SetPattern(Breathing::BOX)) }>
+                        { "Box 4-4-4-4" }
+                    </button>
+                    <button type="button" class="btn btn-outline-info" onclick={ self.link.callback(|_| Msg::SetPattern(Breathing::RELAXING)) }>
+                        { "Relaxing 4-7-8" }
+                    </button>
+                </div>
+                <div class="form-row">
+                    { for phases.iter().map(|(breath, id, title, t, min)| {
+                        let breath = *breath;
+                        html! {
+                            <div class="col-sm-3">
+                                <h3 class="center"><strong>{ title }</strong></h3>
+                                <label for={ *id }>{ format!("Seconds: {}", t) }</label>
+                                <input type="range" min={ min } max="20", value={ t } class="custom-range" id={ *id }
+                                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateBreath(breath, e.value)) }
+                                />
+                            </div>
+                        }
+                    }) }
+                </div>
+                <div class="form-row">
+                    <div class="col-sm-4 offset-sm-4">
+                        <h3 class="center"><strong>{"Breaths"}</strong></h3>
+                        <label for="breaths">{ format!("{}", breathing.breaths) }</label>
+                        <input type="range" min="1" max="50", value={ breathing.breaths } class="custom-range" id="breaths"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateBreaths(e.value)) }
+                        />
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the settings of the meditation mode.
+    fn view_meditation(&self) -> Html {
+        html! {
+            <div class="form-row">
+                <div class="col-sm-4 offset-sm-2">
+                    { self.view_duration(Field::Meditation, "meditation", "Duration") }
+                </div>
+                <div class="col-sm-4">
+                    { self.view_duration(Field::Bell, "bell", "Bell Every") }
+                    <div class="custom-control custom-checkbox">
+                        <input type="checkbox" class="custom-control-input" id="hideDigits" checked={ self.settings.meditation.hide_digits }
+                            onclick={ self.link.callback(|_| Msg::ToggleHideDigits) }
+                        />
+                        <label class="custom-control-label" for="hideDigits">{ "Hide the remaining time" }</label>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Render the settings of the stretching mode: the list of stretches and the transitions.
+    fn view_stretching(&self) -> Html {
+        html! {
+            <>
+                <h3 class="center"><strong>{"Stretches"}</strong></h3>
+                <ul class="list-group mb-2">
+                    { for self.settings.stretching.stretches.iter().enumerate().map(|(i, stretch)| html! {
+                        <li class="list-group-item">
+                            <div class="input-group input-group-sm">
+                                <input type="text" class="form-control" value={ stretch.name.clone() }
+                                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateStretchName(i, e.value)) }
+                                />
+                                <input type="text" class="form-control text-center" style="max-width: 5em;" title="e.g. 30, 45s or 1:00"
+                                    value={ duration::format(stretch.hold) }
+                                    onchange={ self.link.callback(move |e: ChangeData| match e {
+                                        ChangeData::Value(v) => Msg::UpdateStretchHold(i, v),
+                                        _ => Msg::UpdateStretchHold(i, String::new()),
+                                    }) }
+                                />
+                                <div class="input-group-append">
+                                    <button type="button" title="Hold on both sides"
+                                        class={ if stretch.bilateral { "btn btn-info" } else { "btn btn-outline-info" } }
+                                        onclick={ self.link.callback(move |_| Msg::ToggleBilateral(i)) }>{ "L/R" }</button>
+                                    <button type="button" class="btn btn-outline-danger" onclick={ self.link.callback(move |_| Msg::RemoveStretch(i)) }>
+                                        <i class="fa fa-times" aria-hidden="true"></i>
+                                    </button>
+                                </div>
+                            </div>
+                        </li>
+                    }) }
+                </ul>
+                <button type="button" class="btn btn-outline-info btn-sm mb-3" onclick={ self.link.callback(|_| Msg::AddStretch) }>{ "Add stretch" }</button>
+                <div class="form-row">
+                    <div class="col-sm-4 offset-sm-2">
+                        { self.view_duration(Field::Switch, "switch", "Switch Sides") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::StretchRest, "stretchRest", "Rest") }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the settings of the ladder mode, including a preview of the generated intervals.
+    fn view_ladder(&self) -> Html {
+        let ladder = &self.settings.ladder;
+        let rungs = ladder.rungs().iter().map(|t| duration::format(*t)).collect::<Vec<String>>();
+
+        html! {
+            <>
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::LadderLow, "ladderLow", "From") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::LadderHigh, "ladderHigh", "To") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::LadderIncrement, "ladderIncrement", "Increment") }
+                    </div>
+                </div>
+                <div class="form-row">
+                    <div class="col-sm-4 offset-sm-2">
+                        <h3 class="center"><strong>{"Shape"}</strong></h3>
+                        <select class="custom-select" id="shape"
+                            onchange={ self.link.callback(|e: ChangeData| match e {
+                                ChangeData::Select(s) => Msg::UpdateShape(s.selected_index()),
+                                _ => Msg::UpdateShape(-1),
+                            }) }
+                        >
+                            { for Shape::ALL.iter().map(|shape| html! {
+                                <option selected={ *shape == ladder.shape }>{ shape.name() }</option>
+                            }) }
+                        </select>
+                    </div>
+                    <div class="col-sm-4">
+                        <h3 class="center"><strong>{"Rest"}</strong></h3>
+                        <label for="ladderRest">
+                            { if ladder.proportional { format!("{}% of the work", ladder.rest) } else { format!("Seconds: {}", ladder.rest) } }
+                        </label>
+                        <input type="range" min="0" max={ if ladder.proportional { "200" } else { "120" } }, value={ ladder.rest } class="custom-range" id="ladderRest"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateLadderRest(e.value)) }
+                        />
+                        <div class="custom-control custom-checkbox">
+                            <input type="checkbox" class="custom-control-input" id="proportional" checked={ ladder.proportional }
+                                onclick={ self.link.callback(|_| Msg::ToggleProportional) }
+                            />
+                            <label class="custom-control-label" for="proportional">{ "Proportional to the work" }</label>
+                        </div>
+                    </div>
+                </div>
+                <p class="mt-3 mb-0">{ rungs.join(" · ") }</p>
+                <p><strong>{ format!("Total: {}", duration::format(ladder.program().duration())) }</strong></p>
+            </>
+        }
+    }
+
+    /// Render the settings of the custom routine mode: the list of steps.
+    fn view_routine(&self) -> Html {
+        html! {
+            <>
+                <h3 class="center"><strong>{"Steps"}</strong></h3>
+                <ul class="list-group mb-2">
+                    { for self.settings.routine.entries.iter().enumerate().map(|(i, entry)| html! {
+                        <li class="list-group-item">
+                            <div class="input-group input-group-sm">
+                                <input type="text" class="form-control" value={ entry.name.clone() }
+                                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateEntryName(i, e.value)) }
+                                />
+                                <input type="text" class="form-control text-center" style="max-width: 5em;" title="e.g. 30, 45s or 1:00"
+                                    value={ if entry.manual { String::new() } else { duration::format(entry.duration) } }
+                                    disabled={ entry.manual }
+                                    onchange={ self.link.callback(move |e: ChangeData| match e {
+                                        ChangeData::Value(v) => Msg::UpdateEntryDuration(i, v),
+                                        _ => Msg::UpdateEntryDuration(i, String::new()),
+                                    }) }
+                                />
+                                <div class="input-group-append">
+                                    <button type="button" title="Lasts until you are done"
+                                        class={ if entry.manual { "btn btn-info" } else { "btn btn-outline-info" } }
+                                        onclick={ self.link.callback(move |_| Msg::ToggleManual(i)) }>{ "Reps" }</button>
+                                    <button type="button" title="Rest instead of work"
+                                        class={ if entry.rest { "btn btn-warning" } else { "btn btn-outline-warning" } }
+                                        onclick={ self.link.callback(move |_| Msg::ToggleRest(i)) }>{ "Rest" }</button>
+                                    <button type="button" class="btn btn-outline-danger" onclick={ self.link.callback(move |_| Msg::RemoveEntry(i)) }>
+                                        <i class="fa fa-times" aria-hidden="true"></i>
+                                    </button>
+                                </div>
+                            </div>
+                        </li>
+                    }) }
+                </ul>
+                <button type="button" class="btn btn-outline-info btn-sm mb-3" onclick={ self.link.callback(|_| Msg::AddEntry) }>{ "Add step" }</button>
+            </>
+        }
+    }
+
+    /// Render an editable list of names.
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The list to render.
+    /// * `title` - The heading above the list.
+    /// * `add` - The caption of the button that appends a name.
+    fn view_names(&self, list: List, title: &str, add: &str) -> Html {
+        let names = match list {
+            List::Stations => &self.settings.circuit.stations,
+            List::Participants => &self.settings.partner.participants,
+            List::Players => &self.settings.chess.players,
+        };
+
+        html! {
+            <>
+                <h3 class="center"><strong>{ title }</strong></h3>
+                <ul class="list-group mb-2">
+                    { for names.iter().enumerate().map(|(i, name)| html! {
+                        <li class="list-group-item">
+                            <div class="input-group input-group-sm">
+                                <div class="input-group-prepend">
+                                    <span class="input-group-text">{ i + 1 }</span>
+                                </div>
+                                <input type="text" class="form-control" value={ name.clone() }
+                                    oninput={ self.link.callback(move |e: InputData| Msg::UpdateName(list, i, e.value)) }
+                                />
+                                <div class="input-group-append">
+                                    <button type="button" class="btn btn-outline-danger" onclick={ self.link.callback(move |_| Msg::RemoveName(list, i)) }>
+                                        <i class="fa fa-times" aria-hidden="true"></i>
+                                    </button>
+                                </div>
+                            </div>
+                        </li>
+                    }) }
+                </ul>
+                <button type="button" class="btn btn-outline-info btn-sm mb-3" onclick={ self.link.callback(move |_| Msg::AddName(list)) }>{ add }</button>
+            </>
+        }
+    }
+
+    /// Render the settings of the circuit mode: the stations and the timing.
+    fn view_circuit(&self) -> Html {
+        html! {
+            <>
+                { self.view_names(List::Stations, "Stations", "Add station") }
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::StationWork, "stationWork", "Work") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::StationRest, "stationRest", "Rotate") }
+                    </div>
+                    <div class="col-sm-4">
+                        <h3 class="center"><strong>{"Rounds"}</strong></h3>
+                        <label for="circuitRounds">{ format!("{}", self.settings.circuit.rounds) }</label>
+                        <input type="range" min="1" max="10", value={ self.settings.circuit.rounds } class="custom-range" id="circuitRounds"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateCircuitRounds(e.value)) }
+                        />
+                        { self.view_duration(Field::CircuitRest, "circuitRest", "Round Rest") }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the settings of the partner mode: the participants and the timing.
+    fn view_partner(&self) -> Html {
+        html! {
+            <>
+                { self.view_names(List::Participants, "Participants", "Add participant") }
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::TurnWork, "turnWork", "Work") }
+                    </div>
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::TurnRest, "turnRest", "Rest") }
+                    </div>
+                    <div class="col-sm-4">
+                        <h3 class="center"><strong>{"Turns"}</strong></h3>
+                        <label for="turns">{ format!("{}", self.settings.partner.turns) }</label>
+                        <input type="range" min="1" max="60", value={ self.settings.partner.turns } class="custom-range" id="turns"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateTurns(e.value)) }
+                        />
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the settings of the chess clock: the players, their time bank and the increment.
+    fn view_chess(&self) -> Html {
+        let chess = &self.settings.chess;
+
+        html! {
+            <>
+                { self.view_names(List::Players, "Players", "Add player") }
+                <div class="form-row">
+                    <div class="col-sm-4">
+                        { self.view_duration(Field::Bank, "bank", "Time") }
+                    </div>
+                    <div class="col-sm-4">
+                        <h3 class="center"><strong>{"Increment"}</strong></h3>
+                        <select class="custom-select" id="increment"
+                            onchange={ self.link.callback(|e: ChangeData| match e {
+                                ChangeData::Select(s) => Msg::UpdateIncrement(s.selected_index()),
+                                _ => Msg::UpdateIncrement(-1),
+                            }) }
+                        >
+                            { for Increment::ALL.iter().map(|increment| html! {
+                                <option selected={ *increment == chess.increment }>{ increment.name() }</option>
+                            }) }
+                        </select>
+                    </div>
+                    <div class="col-sm-4">
+                        { if chess.increment == Increment::None {
+                            html! {}
+                        } else {
+                            self.view_duration(Field::Bonus, "bonus", "Bonus")
+                        } }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Render the preset library.
+    fn view_presets(&self) -> Html {
+        html! {
+            <>
+                <h3 class="center"><strong>{"Presets"}</strong></h3>
+                <div class="input-group mb-3">
+                    <input type="text" class="form-control" placeholder="Preset name" value={ self.preset_name.clone() }
+                        oninput={ self.link.callback(|e: InputData| Msg::UpdatePresetName(e.value)) }
+                    />
+                    <div class="input-group-append">
+                        <button type="button" class="btn btn-outline-info" onclick={ self.link.callback(|_| Msg::SavePreset) }>{ "Save" }</button>
+                    </div>
+                </div>
+                <ul class="list-group">
+                    { for self.presets.iter().enumerate().map(|(i, preset)| html! {
+                        <li class="list-group-item d-flex justify-content-between align-items-center">
+                            { &preset.name }
+                            <div class="btn-group btn-group-sm" role="group">
+                                <button type="button" class="btn btn-outline-info" onclick={ self.link.callback(move |_| Msg::LoadPreset(i)) }>{ "Pick" }</button>
+                                <button type="button" class="btn btn-outline-secondary" title="Rename to the name entered above"
+                                    onclick={ self.link.callback(move |_| Msg::RenamePreset(i)) }>{ "Rename" }</button>
+                                <button type="button" class="btn btn-outline-danger" onclick={ self.link.callback(move |_| Msg::DeletePreset(i)) }>{ "Delete" }</button>
+                            </div>
+                        </li>
+                    }) }
+                </ul>
+            </>
+        }
+    }
 }
 
 impl Component for Form {
@@ -32,9 +728,9 @@ impl Component for Form {
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         Self {
             link,
-            on: 20,
-            off: 10,
-            cycles: 8,
+            settings: props.settings,
+            presets: Preset::load_all(),
+            preset_name: String::new(),
             callback: props.callback,
             text: "",
         }
@@ -42,141 +738,350 @@ impl Component for Form {
 
     fn update(&mut self, msg: Self::Message) -> bool {
         match msg {
-            Msg::UpdateOnH(hou) => {
-                let res = hou.parse::<u64>();
-
-                match res {
-                    Ok(h) => {
-                        let mut temp = self.on % 3600; // strip hours
-                        temp += h * 3600;
-                        self.on = temp;
-                    },
-                    Err(_) => {},
+            Msg::UpdateMode(i) => {
+                if let Some(mode) = Mode::ALL.get(i as usize) {
+                    self.settings.mode = *mode;
                 }
             }
-            Msg::UpdateOnM(min) => {
-                let res = min.parse::<u64>();
-
-                match res {
-                    Ok(m) => {
-                        let mut temp = self.on - minutes(self.on) * 60; // strip minutes
-                        temp += m * 60;
-                        self.on = temp;
-                    },
-                    Err(_) => {},
+            Msg::UpdateHours(field, hou) => {
+                if let Ok(h) = hou.parse::<u64>() {
+                    let t = field.of(&mut self.settings);
+                    *t = *t % 3600 + h * 3600; // strip hours
                 }
             }
-            Msg::UpdateOnS(sec) => {
-                let res = sec.parse::<u64>();
-
-                match res {
-                    Ok(s) => {
-                        let mut temp = self.on - seconds(self.on); // strip seconds
-                        temp += s;
-                        self.on = temp;
-                    },
-                    Err(_) => {},
+            Msg::UpdateMinutes(field, min) => {
+                if let Ok(m) = min.parse::<u64>() {
+                    let t = field.of(&mut self.settings);
+                    *t = *t - minutes(*t) * 60 + m * 60; // strip minutes
                 }
             }
-            Msg::UpdateOffH(hou) => {
-                let res = hou.parse::<u64>();
-
-                match res {
-                    Ok(h) => {
-                        let mut temp = self.off % 3600; // strip hours
-                        temp += h * 3600;
-                        self.off = temp;
-                    },
-                    Err(_) => {},
+            Msg::UpdateSeconds(field, sec) => {
+                if let Ok(s) = sec.parse::<u64>() {
+                    let t = field.of(&mut self.settings);
+                    *t = *t - seconds(*t) + s; // strip seconds
                 }
             }
-            Msg::UpdateOffM(min) => {
-                let res = min.parse::<u64>();
-
-                match res {
-                    Ok(m) => {
-                        let mut temp = self.off - minutes(self.off) * 60; // strip minutes
-                        temp += m * 60;
-                        self.off = temp;
-                    },
-                    Err(_) => {},
+            Msg::ParseDuration(field, text) => {
+                if let Some(t) = duration::parse(&text) {
+                    *field.of(&mut self.settings) = t;
+                }
+            }
+            Msg::UpdateCycles(cyc) => {
+                if let Ok(c) = cyc.parse::<u64>() {
+                    self.settings.workout.cycles = c;
+                }
+            }
+            Msg::UpdateSets(set) => {
+                if let Ok(s) = set.parse::<u64>() {
+                    self.settings.workout.sets = s;
                 }
             }
-            Msg::UpdateOffS(sec) => {
-                let res = sec.parse::<u64>();
+            Msg::UpdateRounds(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.emom.rounds = n;
+                }
+            }
+            Msg::UpdateLabels(text) => { // Comma separated, empty labels are dropped
+                self.settings.emom.labels = text.split(',')
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect();
+            }
+            Msg::UpdateSessions(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.pomodoro.sessions = n;
+                }
+            }
+            Msg::ToggleAutoStart => self.settings.pomodoro.auto_start = !self.settings.pomodoro.auto_start,
+            Msg::UpdateBoxingRounds(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.boxing.rounds = n;
+                }
+            }
+            Msg::UpdateWarning(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.boxing.warning = n;
+                }
+            }
+            Msg::UpdateBreath(breath, sec) => {
+                if let Ok(s) = sec.parse::<u64>() {
+                    let breathing = &mut self.settings.breathing;
+                    match breath {
+                        Breath::Inhale => breathing.inhale = s,
+                        Breath::HoldFull => breathing.hold_full = s,
+                        Breath::Exhale => breathing.exhale = s,
+                        Breath::HoldEmpty => breathing.hold_empty = s,
+                    }
+                }
+            }
+            Msg::UpdateBreaths(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.breathing.breaths = n;
+                }
+            }
+            Msg::SetPattern([inhale, hold_full, exhale, hold_empty]) => {
+                let breaths = self.settings.breathing.breaths;
+                self.settings.breathing = Breathing { breaths, ..Breathing::pattern(inhale, hold_full, exhale, hold_empty) };
+            }
+            Msg::ToggleHideDigits => self.settings.meditation.hide_digits = !self.settings.meditation.hide_digits,
+            Msg::AddStretch => self.settings.stretching.stretches.push(Stretch::default()),
+            Msg::RemoveStretch(i) => {
+                if i < self.settings.stretching.stretches.len() {
+                    self.settings.stretching.stretches.remove(i);
+                }
+            }
+            Msg::UpdateStretchName(i, name) => {
+                if let Some(stretch) = self.settings.stretching.stretches.get_mut(i) {
+                    stretch.name = name;
+                }
+            }
+            Msg::UpdateStretchHold(i, text) => {
+                if let (Some(stretch), Some(t)) = (self.settings.stretching.stretches.get_mut(i), duration::parse(&text)) {
+                    stretch.hold = t;
+                }
+            }
+            Msg::ToggleBilateral(i) => {
+                if let Some(stretch) = self.settings.stretching.stretches.get_mut(i) {
+                    stretch.bilateral = !stretch.bilateral;
+                }
+            }
+            Msg::UpdateShape(i) => {
+                if let Some(shape) = Shape::ALL.get(i as usize) {
+                    self.settings.ladder.shape = *shape;
+                }
+            }
+            Msg::UpdateLadderRest(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.ladder.rest = n;
+                }
+            }
+            Msg::ToggleProportional => self.settings.ladder.proportional = !self.settings.ladder.proportional,
+            Msg::AddEntry => self.settings.routine.entries.push(Entry::default()),
+            Msg::RemoveEntry(i) => {
+                if i < self.settings.routine.entries.len() {
+                    self.settings.routine.entries.remove(i);
+                }
+            }
+            Msg::UpdateEntryName(i, name) => {
+                if let Some(entry) = self.settings.routine.entries.get_mut(i) {
+                    entry.name = name;
+                }
+            }
+            Msg::UpdateEntryDuration(i, text) => {
+                if let (Some(entry), Some(t)) = (self.settings.routine.entries.get_mut(i), duration::parse(&text)) {
+                    entry.duration = t;
+                }
+            }
+            Msg::ToggleManual(i) => {
+                if let Some(entry) = self.settings.routine.entries.get_mut(i) {
+                    entry.manual = !entry.manual;
+                    entry.rest &= !entry.manual; // only work can be counted in reps
+                }
+            }
+            Msg::ToggleRest(i) => {
+                if let Some(entry) = self.settings.routine.entries.get_mut(i) {
+                    entry.rest = !entry.rest;
+                    entry.manual &= !entry.rest;
+                }
+            }
+            Msg::AddName(list) => {
+                let names = self.names(list);
+                let name = match list {
+                    List::Stations => format!("Station {}", names.len() + 1),
+                    List::Participants => format!("Partner {}", names.len() + 1),
+                    List::Players => format!("Player {}", names.len() + 1),
+                };
+                names.push(name);
+            }
+            Msg::RemoveName(list, i) => {
+                let names = self.names(list);
+                if i < names.len() {
+                    names.remove(i);
+                }
+            }
+            Msg::UpdateName(list, i, name) => {
+                if let Some(n) = self.names(list).get_mut(i) {
+                    *n = name;
+                }
+            }
+            Msg::UpdateTurns(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.partner.turns = n;
+                }
+            }
+            Msg::UpdateIncrement(i) => {
+                if let Some(increment) = Increment::ALL.get(i as usize) {
+                    self.settings.chess.increment = *increment;
+                }
+            }
+            Msg::UpdateCircuitRounds(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.circuit.rounds = n;
+                }
+            }
+            Msg::UpdateLeadIn(sec) => {
+                if let Ok(s) = sec.parse::<u64>() {
+                    self.settings.cues.lead_in = s;
+                }
+            }
+            Msg::UpdateBeeps(num) => {
+                if let Ok(n) = num.parse::<u64>() {
+                    self.settings.cues.beeps = n;
+                }
+            }
+            Msg::ToggleHalfway => self.settings.cues.halfway = !self.settings.cues.halfway,
+            Msg::ToggleLastRound => self.settings.cues.last_round = !self.settings.cues.last_round,
+            Msg::ToggleOneMinute => self.settings.cues.one_minute = !self.settings.cues.one_minute,
+            Msg::ToggleTenSeconds => self.settings.cues.ten_seconds = !self.settings.cues.ten_seconds,
+            Msg::ToggleAlarm => self.settings.countdown.alarm = !self.settings.countdown.alarm,
+            Msg::UpdatePresetName(name) => {
+                self.preset_name = name;
+                return true; // nothing to emit
+            }
+            Msg::SavePreset => { // Save the current settings, presets with the same name are replaced
+                let name = self.preset_name.trim().to_string();
+                if name.is_empty() {
+                    return false;
+                }
 
-                match res {
-                    Ok(s) => {
-                        let mut temp = self.off - seconds(self.off); // strip seconds
-                        temp += s;
-                        self.off = temp;
-                    },
-                    Err(_) => {},
+                let preset = Preset { name, settings: self.settings.clone() };
+                match self.presets.iter_mut().find(|p| p.name == preset.name) {
+                    Some(p) => *p = preset,
+                    None => self.presets.push(preset),
                 }
+                Preset::save_all(&self.presets);
+                return true;
             }
-            Msg::UpdateCycles(cyc) => {
-                let res = cyc.parse::<u64>();
+            Msg::LoadPreset(i) => {
+                if let Some(preset) = self.presets.get(i) {
+                    self.settings = preset.settings.clone();
+                }
+            }
+            Msg::RenamePreset(i) => {
+                let name = self.preset_name.trim().to_string();
+                if name.is_empty() {
+                    return false;
+                }
 
-                match res {
-                    Ok(c) => {
-                        self.cycles = c;
-                    },
-                    Err(_) => {},
+                if let Some(preset) = self.presets.get_mut(i) {
+                    preset.name = name;
+                }
+                Preset::save_all(&self.presets);
+                return true;
+            }
+            Msg::DeletePreset(i) => {
+                if i < self.presets.len() {
+                    self.presets.remove(i);
                 }
+                Preset::save_all(&self.presets);
+                return true;
+            }
+            Msg::CopyShareLink => {
+                share::copy_link(&self.settings, self.link.callback(Msg::LinkCopied));
+                return false;
+            }
+            Msg::LinkCopied(copied) => {
+                self.text = if copied { "Link copied to the clipboard" } else { "The link couldn't be copied" };
+                return true;
             }
         }
 
-        self.callback.emit((self.on, self.off, self.cycles));
+        self.callback.emit(self.settings.clone());
         true
     }
 
     fn change(&mut self, props: Self::Properties) -> bool {
+        self.settings = props.settings;
         self.callback = props.callback;
         true
     }
 
     fn view(&self) -> Html {
         html! {
-            <form>
+            // Enter in a text input submits the form, which would reload the page.
+            <form onsubmit={ self.link.batch_callback(|e: FocusEvent| { e.prevent_default(); Vec::new() }) }>
+                <div class="form-group">
+                    <label for="mode"><strong>{ "Mode" }</strong></label>
+                    <select class="custom-select" id="mode"
+                        onchange={ self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(s) => Msg::UpdateMode(s.selected_index()),
+                            _ => Msg::UpdateMode(-1),
+                        }) }
+                    >
+                        { for Mode::ALL.iter().map(|mode| html! {
+                            <option selected={ *mode == self.settings.mode }>{ mode.name() }</option>
+                        }) }
+                    </select>
+                </div>
+                <hr/>
+                { match self.settings.mode {
+                    Mode::Interval => self.view_interval(),
+                    Mode::Stopwatch => self.view_stopwatch(),
+                    Mode::Countdown => self.view_countdown(),
+                    Mode::Emom => self.view_emom(),
+                    Mode::Pomodoro => self.view_pomodoro(),
+                    Mode::Boxing => self.view_boxing(),
+                    Mode::Breathing => self.view_breathing(),
+                    Mode::Meditation => self.view_meditation(),
+                    Mode::Stretching => self.view_stretching(),
+                    Mode::Ladder => self.view_ladder(),
+                    Mode::Routine => self.view_routine(),
+                    Mode::Circuit => self.view_circuit(),
+                    Mode::Partner => self.view_partner(),
+                    Mode::Chess => self.view_chess(),
+                } }
+                <hr/>
                 <div class="form-row">
                     <div class="col-sm-4">
-                        <h3 class="center"><strong>{"On Time"}</strong></h3>
-                        <label for="onHour">{ format!("Hours: {}", hours(self.on)) }</label>
-                        <input type="range" min="0" max="23", value={ hours(self.on) } class="custom-range" id="onHour"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateOnH(e.value)) }
-                        />
-                        <label for="onMinute">{ format!("Minutes: {}", minutes(self.on)) }</label>
-                        <input type="range" min="0" max="59", value={ minutes(self.on) } class="custom-range" id="onMinute"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateOnM(e.value)) }
-                        />
-                        <label for="onSecond">{ format!("Seconds: {}", seconds(self.on)) }</label>
-                        <input type="range" min="0" max="59", value={ seconds(self.on) } class="custom-range" id="onSecond"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateOnS(e.value)) }
+                        <h3 class="center"><strong>{"Lead-in"}</strong></h3>
+                        <label for="leadIn">{ format!("Seconds: {}", self.settings.cues.lead_in) }</label>
+                        <input type="range" min="0" max="30", value={ self.settings.cues.lead_in } class="custom-range" id="leadIn"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateLeadIn(e.value)) }
                         />
                     </div>
                     <div class="col-sm-4">
-                        <h3 class="center"><strong>{"Off Time"}</strong></h3>
-                        <label for="offHour">{ format!("Hours: {}", hours(self.off)) }</label>
-                        <input type="range" min="0" max="23", value={ hours(self.off) } class="custom-range" id="offHour"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateOffH(e.value)) }
-                        />
-                        <label for="offMinute">{ format!("Minutes: {}", minutes(self.off)) }</label>
-                        <input type="range" min="0" max="59", value={ minutes(self.off) } class="custom-range" id="offMinute"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateOffM(e.value)) }
-                        />
-                        <label for="offSecond">{ format!("Seconds: {}", seconds(self.off)) }</label>
-                        <input type="range" min="0" max="59", value={ seconds(self.off) } class="custom-range" id="offSecond"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateOffS(e.value)) }
+                        <h3 class="center"><strong>{"Beeps"}</strong></h3>
+                        <label for="beeps">{ format!("{}", self.settings.cues.beeps) }</label>
+                        <input type="range" min="0" max="10", value={ self.settings.cues.beeps } class="custom-range" id="beeps"
+                            oninput={ self.link.callback(|e: InputData| Msg::UpdateBeeps(e.value)) }
                         />
                     </div>
-                    <div class="col-sm-4">
-                        <h3 class="center"><strong>{"Cycles"}</strong></h3>
-                        <label for="cycles">{ format!("{}", self.cycles) }</label>
-                        <input type="range" min="1" max="100", value={ self.cycles } class="custom-range" id="cycles"
-                            oninput={ self.link.callback(|e: InputData| Msg::UpdateCycles(e.value)) }
-                        />
+                    <div class="col-sm-4 text-left">
+                        <h3 class="center"><strong>{"Cues"}</strong></h3>
+                        <div class="custom-control custom-checkbox">
+                            <input type="checkbox" class="custom-control-input" id="cueHalfway" checked={ self.settings.cues.halfway }
+                                onclick={ self.link.callback(|_| Msg::ToggleHalfway) }
+                            />
+                            <label class="custom-control-label" for="cueHalfway">{ "Halfway" }</label>
+                        </div>
+                        <div class="custom-control custom-checkbox">
+                            <input type="checkbox" class="custom-control-input" id="cueLastRound" checked={ self.settings.cues.last_round }
+                                onclick={ self.link.callback(|_| Msg::ToggleLastRound) }
+                            />
+                            <label class="custom-control-label" for="cueLastRound">{ "Last round" }</label>
+                        </div>
+                        <div class="custom-control custom-checkbox">
+                            <input type="checkbox" class="custom-control-input" id="cueOneMinute" checked={ self.settings.cues().one_minute }
+                                disabled={ self.settings.mode == Mode::Countdown } // always on
+                                onclick={ self.link.callback(|_| Msg::ToggleOneMinute) }
+                            />
+                            <label class="custom-control-label" for="cueOneMinute">{ "1 minute left" }</label>
+                        </div>
+                        <div class="custom-control custom-checkbox">
+                            <input type="checkbox" class="custom-control-input" id="cueTenSeconds" checked={ self.settings.cues.ten_seconds }
+                                onclick={ self.link.callback(|_| Msg::ToggleTenSeconds) }
+                            />
+                            <label class="custom-control-label" for="cueTenSeconds">{ "10 seconds left" }</label>
+                        </div>
                     </div>
                 </div>
+                <hr/>
+                { self.view_presets() }
+                <hr/>
+                <button type="button" class="btn btn-outline-info" onclick={ self.link.callback(|_| Msg::CopyShareLink) }>
+                    <i class="fa fa-link" aria-hidden="true"></i>{ " Copy share link" }
+                </button>
+                <p class="mt-2 mb-0">{ self.text }</p>
             </form>
         }
     }
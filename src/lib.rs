@@ -1,210 +1,191 @@
 #![recursion_limit="1024"] // limit the recursion depth of the html! macro
 mod helper;
+mod duration;
 mod clock;
 mod form;
+pub mod engine;
+pub mod program;
+mod settings;
+mod session;
+mod share;
+mod tally;
+mod timer;
+pub mod turns;
 
 use wasm_bindgen::prelude::*;
 use yew::prelude::*;
-use yew::services::{Task, IntervalService, ConsoleService};
 
-use helper::{hours, minutes, seconds};
-use clock::Clock;
 use form::Form;
-use wasm_bindgen::__rt::core::time::Duration;
+use settings::Settings;
+use timer::Timer;
 
 
-/// This represents the upper layer of an interval timer.
+/// The maximum number of timers on the dashboard.
+const MAX_TIMERS: usize = 6;
+
+/// This represents the page of the app: a dashboard of one or more `Timer`s.
+///
+/// Each timer has its own settings and controls, the settings `Form` edits the selected one.
+/// With a single timer the dashboard is just a plain interval timer, with more of them (e.g. a
+/// coach running a separate timer for each group) they are shown side by side and can be
+/// started all at once.
 ///
-/// A interval timer (also known as ta-ba-ta timer) loops between `on` and `off` state until all
-/// cycles of a training are completed.
-struct Timer {
+/// Only the settings of the first timer are saved, it's also the one whose workout is resumed
+/// after a reload.
+struct Dashboard {
     link: ComponentLink<Self>,
-    duration_on: u64,   // duration of each cycle in seconds
-    duration_off: u64,  // duration of each pause in seconds
-    cycles: u64,        // total number of rounds
-    start: u64,         // seconds until the timer starts
-    counter_s: u64,
-    counter_c: u64,
-    callback_tick: Callback<()>, // callback to be invoked on a `tick`
-    callback_form: Callback<(u64, u64, u64)>,
-    message: &'static str,
-    state: State,       // the current state of the timer
-    saved_state: State, // Used to save the state if the timer is paused.
-    job: Option<Box<dyn Task>>, // Currently active task
+    timers: Vec<Slot>,
+    selected: usize,    // index of the timer being edited
+    next_id: usize,     // id of the next timer added
+    start_all: u64,     // passed on to the timers, incremented to start all of them
+    callback_form: Callback<Settings>,
+}
+
+/// A timer on the `Dashboard`, the id keeps it apart from the others when one is removed.
+struct Slot {
+    id: usize,
+    settings: Settings,
 }
 
-/// Messages the `Timer` can handle.
+/// Messages the `Dashboard` can handle.
 ///
 /// # Messages
 ///
-/// * `StartTimer` - Starts the timer.
-/// * `StopTimer` - Stops the timer (state is preserved).
-/// * `ResetTimer` - Resets everything to the currently selected settings.
-/// * `SetTimer` - Set a new On and Off duration as well as a new number of cycles to complete.
-/// * `Tick` - Frequently called (each second) by an `IntervalService` if the timer is active (`On`, `Off`).
+/// * `Configure` - Apply new settings to the selected timer.
+/// * `Select` - Select the timer to edit.
+/// * `AddTimer` - Add a timer with the settings of the selected one.
+/// * `RemoveTimer` - Remove a timer (all but the first).
+/// * `StartAll` - Start (or resume) all timers at once.
 enum Msg {
-    StartTimer,
-    StopTimer,
-    ResetTimer,
-    SetTimer(u64, u64, u64),
-    Tick,
+    Configure(Box<Settings>),
+    Select(usize),
+    AddTimer,
+    RemoveTimer(usize),
+    StartAll,
 }
 
-/// The different states of the `Timer`.
-///
-/// The `Timer` starts in `Idle` state. If the user clicks the start button the `Timer` switches
-/// to the `Start` state and a countdown appears after which it transitions to the `On` state.
-/// The `Timer` toggles between `On` and `Off` until either all cycles are completed or the user
-/// presses the pause button. In `Pause` state the timer can either be resumed or reset.
-///
-/// # States
+/// Properties of the `Dashboard`.
 ///
-/// * `Start` - Start/ Resume the timer.
-/// * `On` - The state in which the user is demanded to work out.
-/// * `Off` - The state in which the user is granted some rest.
-/// * `Paused` - The timer is paused.
-/// * `Idle` - Do nothing.
-#[derive(Copy, Clone, PartialEq)]
-enum State {
-    Start,
-    On,
-    Off,
-    Paused,
-    Idle,
+/// * `shared` - Settings shared via the url the page has been opened with.
+#[derive(Clone, PartialEq, Properties)]
+struct Props {
+    shared: Option<Settings>,
+}
+
+impl Dashboard {
+    /// Create the timers, side by side if there is more than one.
+    ///
+    /// The layout is the same for a single timer, so a running timer isn't recreated when
+    /// another one is added.
+    fn view_timers(&self) -> Html {
+        let single = self.timers.len() == 1;
+        html! {
+            <div class="row">
+                { for self.timers.iter().enumerate().map(|(i, slot)| html! {
+                    <div class={ if single { "col-12" } else { "col-md-6 mb-4" } } key={ slot.id.to_string() }>
+                        { if single { html! {} } else { self.view_header(i, slot) } }
+                        <Timer settings={ slot.settings.clone() } primary={ i == 0 } start_all={ self.start_all } />
+                    </div>
+                }) }
+            </div>
+        }
+    }
+
+    /// Create the header of a timer with the buttons to edit and remove it.
+    fn view_header(&self, i: usize, slot: &Slot) -> Html {
+        html! {
+            <div class="d-flex justify-content-between align-items-center mb-2">
+                <strong>{ format!("Timer {} · {}", i + 1, slot.settings.mode.name()) }</strong>
+                <div>
+                    <button type="button" class="btn btn-sm btn-outline-light mr-1" title="Settings" data-toggle="modal" data-target="#settingsModal" onclick=self.link.callback(move |_| Msg::Select(i))>
+                        <i class="fa fa-cog" aria-hidden="true"></i>
+                    </button>
+                    { if i > 0 { html! { // the first timer is the primary one
+                        <button type="button" class="btn btn-sm btn-outline-light" title="Remove" onclick=self.link.callback(move |_| Msg::RemoveTimer(i))>
+                            <i class="fa fa-times" aria-hidden="true"></i>
+                        </button>
+                    } } else { html! {} } }
+                </div>
+            </div>
+        }
+    }
+
+    /// Create the buttons to manage the timers of the dashboard.
+    fn view_dashboard(&self) -> Html {
+        html! {
+            <div class="mt-3">
+                { if self.timers.len() > 1 {
+                    html! { <button type="button" class="btn btn-outline-info mr-3" onclick=self.link.callback(|_| Msg::StartAll)>{ "Start all" }</button> }
+                } else {
+                    html! {}
+                } }
+                <button type="button" class="btn btn-outline-light" disabled={ self.timers.len() >= MAX_TIMERS } onclick=self.link.callback(|_| Msg::AddTimer)>{ "Add timer" }</button>
+            </div>
+        }
+    }
 }
 
-impl Component for Timer {
+impl Component for Dashboard {
     type Message = Msg;
-    type Properties = (); // Root node so we have no properties
+    type Properties = Props;
 
-    /// Create a new `Timer` component.
+    /// Create a new `Dashboard` component.
     ///
     /// # Arguments
     ///
-    /// * `_props` - Properties from the parent component (currently none - it's the root).
+    /// * `props` - Properties passed by `run_app` (it's the root).
     /// * `link` - A link to register callbacks or send messages to the component.
-    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let settings = match props.shared {
+            Some(settings) => { // opened via a share link
+                settings.save();
+                share::clear_location();
+                settings
+            },
+            None => Settings::load(), // restore the last-used configuration
+        };
+
         Self {
-            callback_tick: link.callback(|_| Msg::Tick), // register new `Tick` callback.
-            callback_form: link.callback(|tup: (u64, u64, u64)| Msg::SetTimer(tup.0, tup.1, tup.2)),
+            callback_form: link.callback(|settings| Msg::Configure(Box::new(settings))),
             link,
-            duration_on: 20,
-            duration_off: 10,
-            cycles: 8,
-            start: 5,
-            counter_s: 20,
-            counter_c: 0,
-            message: "",
-            state: State::Idle,
-            saved_state: State::Idle,
-            job: None,
+            timers: vec![Slot { id: 0, settings }],
+            selected: 0,
+            next_id: 1,
+            start_all: 0,
         }
     }
 
     /// Handle incomming messages.
     ///
-    /// The `update()` lifecycle method is called for each message.
-    ///
     /// # Arguments
     ///
     /// * `msg` - The message to handle.
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            // Called when the timer is started or resumed.
-            Msg::StartTimer => {
-                // Create an new `IntervalService` instance that calls `Tick` every second.
-                let handle = IntervalService::spawn(Duration::from_secs(1), self.callback_tick.clone());
-                self.job = Some(Box::new(handle));
-
-                match self.state {
-                    State::Idle => { // Start timer
-                        self.counter_s = self.duration_on;
-                        self.counter_c = 0;
-                        //self.message = "Timer started";
-                        self.state = State::Start;
-                    },
-                    _ => { // Resume timer
-                        //self.message = "Timer resumed";
-                        self.state = self.saved_state;
-                    }
+            Msg::Configure(settings) => {
+                if self.selected == 0 {
+                    settings.save();
                 }
-
+                self.timers[self.selected].settings = *settings;
             },
-            Msg::StopTimer => { // Pause the timer (state is preserved until it is started again or reset)
-                //self.message = "Timer stoped";
-                self.saved_state = self.state;  // Save current state
-                self.state = State::Paused;             // Switch timer into pause state
-                self.job = None;                        // Remove the current interval service that calls tick
-            },
-            Msg::ResetTimer => { // Reset the timer state
-                self.counter_s = self.duration_on;
-                self.counter_c = 0;
-                self.start = 5;
-                self.state = State::Idle;
-                //self.message = "Reset";
-                self.job = None;
-            },
-            Msg::SetTimer(on, off, rounds) => {
-                self.duration_on = on;
-                self.duration_off = off;
-                self.cycles = rounds;
-                self.link.callback(|_| Msg::ResetTimer).emit(());
+            Msg::Select(i) => self.selected = i,
+            Msg::AddTimer => {
+                if self.timers.len() >= MAX_TIMERS {
+                    return false;
+                }
+                let settings = self.timers[self.selected].settings.clone();
+                self.timers.push(Slot { id: self.next_id, settings });
+                self.next_id += 1;
+                self.selected = self.timers.len() - 1;
             },
-            Msg::Tick => { // Called every second to update the timer state
-                match self.state {
-                    State::Start => { // The timer has just bee started and we're counting down.
-
-                        if self.start == 0 { // Countdown finished, switch to `On` state.
-                            self.start = 5;
-                            self.state = State::On;
-                        } else {
-                            self.start -= 1;
-                        }
-
-                        if self.start == 0 { // Play countdown sound.
-                            play_countdown("LongBeep");
-                        } else if self.start <= 4 { // Play countdown sound.
-                            play_countdown("ShortBeep");
-                        }
-                    },
-                    _ => {
-
-
-                        if self.counter_s == 0 { // Counted down
-                            match self.state {
-                                State::On => self.counter_c += 1, // `On` - `Off` cycle completed.
-                                _ => {},
-                            }
-
-                            if self.counter_c < self.cycles { // Not all cycles are completed.
-                                match self.state {
-                                    State::On => {
-                                        self.state = State::Off;
-                                        self.counter_s = self.duration_off;
-                                    },
-                                    State::Off => {
-                                        self.state = State::On;
-                                        self.counter_s = self.duration_on;
-                                    },
-                                    _ => {}, // Should be impossible
-                                }
-                            } else { // All cycles completed, Nice Job !
-                                self.state = State::Idle;
-                                //self.message = "Done, nice work!";
-                                self.job = None;
-                            }
-                        } else {
-                            self.counter_s -= 1; // Decrement counter on every tick.
-                        }
-
-                        if self.counter_s == 0 { // Play countdown sound.
-                            play_countdown("LongBeep");
-                        } else if self.counter_s <= 4 { // Play countdown sound.
-                            play_countdown("ShortBeep");
-                        }
-                    }
+            Msg::RemoveTimer(i) => {
+                if i == 0 || i >= self.timers.len() {
+                    return false;
                 }
+                self.timers.remove(i);
+                self.selected = 0;
             },
+            Msg::StartAll => self.start_all += 1,
         }
 
         true
@@ -230,30 +211,8 @@ impl Component for Timer {
                   </header>
 
                   <main role="main" class="inner cover">
-                    <p class="lead">{ self.message }</p>
-                    <div class="clock-container">
-                        <Clock progress={ self.counter_c as f64 / self.cycles as f64 }
-                               text={ if self.state == State::Start {
-                                        format!("{}", self.start)
-                                      } else {
-                                        format!("{:02}:{:02}:{:02}", hours(self.counter_s), minutes(self.counter_s), seconds(self.counter_s))
-                                      }}
-                               darken={self.state == State::Off}
-                               color="#39c9bb"
-                        />
-                    </div>
-
-                    {
-                        match self.state {
-                            State::Idle => html! { <button type="button" class="btn btn-outline-info btn-lg" onclick=self.link.callback(|_| Msg::StartTimer)>{ "Start" }</button> },
-                            State::Paused => html! { <><button type="button" class="btn btn-outline-info btn-lg mr-3" onclick=self.link.callback(|_| Msg::StartTimer)>{ "Resume" }</button>
-                                                     <button type="button" class="btn btn-outline-warning btn-lg" onclick=self.link.callback(|_| Msg::ResetTimer)>{ "Reset" }</button></>},
-                            State::Start => html! { },
-                            _ => html! { <button type="button" class="btn btn-outline-secondary btn-lg" onclick=self.link.callback(|_| Msg::StopTimer)>{ "Stop" }</button> },
-                        }
-                    }
-
-
+                    { self.view_timers() }
+                    { self.view_dashboard() }
                   </main>
 
                   <footer class="mastfoot mt-auto" style="padding-top: 18px;">
@@ -275,7 +234,7 @@ impl Component for Timer {
                                 </button>
                             </div>
                             <div class="modal-body text-dark" id="settingsModalBody">
-                                <Form callback={ self.callback_form.clone() } />
+                                <Form settings={ self.timers[self.selected].settings.clone() } callback={ self.callback_form.clone() } />
                             </div>
                         </div>
                     </div>
@@ -311,12 +270,7 @@ impl Component for Timer {
     }
 }
 
-#[wasm_bindgen]
-extern "C" {
-    fn play_countdown(aid: &str);
-}
-
 #[wasm_bindgen(start)]
 pub fn run_app() {
-    App::<Timer>::new().mount_to_body();
+    App::<Dashboard>::new().mount_to_body_with_props(Props { shared: share::from_location() });
 }
\ No newline at end of file
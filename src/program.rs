@@ -0,0 +1,955 @@
+use serde::{Deserialize, Serialize};
+
+/// The kind of a `Step`, it decides whether the user is demanded to work out or granted some rest.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Kind {
+    Work,
+    Rest,
+}
+
+/// The phase of a breath during a breathing exercise.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Breath {
+    Inhale,
+    HoldFull,   // hold the breath after inhaling
+    Exhale,
+    HoldEmpty,  // hold the breath after exhaling
+}
+
+impl Breath {
+    /// The name of the phase shown to the user.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Breath::Inhale => "Inhale",
+            Breath::HoldFull | Breath::HoldEmpty => "Hold",
+            Breath::Exhale => "Exhale",
+        }
+    }
+}
+
+/// A single named step of a workout, e.g. a warm-up or a work interval.
+///
+/// An `open` step doesn't end by itself, its `duration` is only a target (`0` for none). A
+/// `manual` step lasts until the user is done, e.g. with a number of reps. The steps of a
+/// breathing exercise carry the phase of the breath.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Step {
+    pub name: String,
+    pub duration: u64,  // duration of the step in seconds
+    pub kind: Kind,
+    pub open: bool,
+    pub manual: bool,
+    pub breath: Option<Breath>,
+}
+
+impl Step {
+    /// Create a new `Work` step.
+    pub fn work(name: &str, duration: u64) -> Self {
+        Self { name: name.to_string(), duration, kind: Kind::Work, open: false, manual: false, breath: None }
+    }
+
+    /// Create a new `Rest` step.
+    pub fn rest(name: &str, duration: u64) -> Self {
+        Self { name: name.to_string(), duration, kind: Kind::Rest, open: false, manual: false, breath: None }
+    }
+
+    /// Create a new open `Work` step with an optional `target` duration.
+    pub fn open(name: &str, target: u64) -> Self {
+        Self { name: name.to_string(), duration: target, kind: Kind::Work, open: true, manual: false, breath: None }
+    }
+
+    /// Create a new manual `Work` step.
+    pub fn manual(name: &str) -> Self {
+        Self { name: name.to_string(), duration: 0, kind: Kind::Work, open: false, manual: true, breath: None }
+    }
+
+    /// Create a new step of a breathing exercise, only inhaling counts as `Work`.
+    pub fn breath(breath: Breath, duration: u64) -> Self {
+        let kind = if breath == Breath::Inhale { Kind::Work } else { Kind::Rest };
+        Self { name: breath.name().to_string(), duration, kind, open: false, manual: false, breath: Some(breath) }
+    }
+}
+
+/// A building block of a `Program`.
+///
+/// # Blocks
+///
+/// * `Step` - A single step.
+/// * `Repeat` - Repeat the given blocks `times` times, the optional `rest` step is inserted
+///   between two repetitions (but not after the last one).
+#[derive(Clone, PartialEq, Debug)]
+pub enum Block {
+    Step(Step),
+    Repeat {
+        times: u64,
+        blocks: Vec<Block>,
+        rest: Option<Step>,
+    },
+}
+
+impl Block {
+    /// Append the steps of this block, in order of execution, to `steps`.
+    fn flatten(&self, steps: &mut Vec<Step>) {
+        match self {
+            Block::Step(step) => steps.push(step.clone()),
+            Block::Repeat { times, blocks, rest } => {
+                for i in 0..*times {
+                    for block in blocks {
+                        block.flatten(steps);
+                    }
+
+                    if let Some(rest) = rest {
+                        if i + 1 < *times {
+                            steps.push(rest.clone());
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// An ordered list of blocks that make up a workout.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Program {
+    pub blocks: Vec<Block>,
+}
+
+impl Program {
+    /// All steps of the program in order of execution (repetitions are unrolled).
+    pub fn steps(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for block in &self.blocks {
+            block.flatten(&mut steps);
+        }
+        steps
+    }
+
+    /// The total duration of the program in seconds (targets of open steps included).
+    pub fn duration(&self) -> u64 {
+        self.steps().iter().map(|s| s.duration).sum()
+    }
+}
+
+/// The settings of an interval workout as configured by the user.
+///
+/// `sets` times `cycles` rounds of `on` and `off`, framed by an optional warm-up and cool-down.
+/// All durations are given in seconds, a duration of `0` omits the corresponding step.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workout {
+    pub warm_up: u64,
+    pub on: u64,
+    pub off: u64,
+    pub cycles: u64,
+    pub sets: u64,
+    pub set_rest: u64,
+    pub cool_down: u64,
+}
+
+impl Default for Workout {
+    fn default() -> Self {
+        Self {
+            warm_up: 0,
+            on: 20,
+            off: 10,
+            cycles: 8,
+            sets: 1,
+            set_rest: 0,
+            cool_down: 0,
+        }
+    }
+}
+
+impl Workout {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let mut blocks = Vec::new();
+
+        if self.warm_up > 0 {
+            blocks.push(Block::Step(Step::rest("Warm-up", self.warm_up)));
+        }
+
+        let set = Block::Repeat {
+            times: self.cycles,
+            blocks: vec![Block::Step(Step::work("Work", self.on))],
+            rest: if self.off > 0 { Some(Step::rest("Rest", self.off)) } else { None },
+        };
+        blocks.push(Block::Repeat {
+            times: self.sets,
+            blocks: vec![set],
+            rest: if self.set_rest > 0 { Some(Step::rest("Set rest", self.set_rest)) } else { None },
+        });
+
+        if self.cool_down > 0 {
+            blocks.push(Block::Step(Step::rest("Cool-down", self.cool_down)));
+        }
+
+        Program { blocks }
+    }
+}
+
+/// The settings of the stopwatch.
+///
+/// The stopwatch counts up until it is stopped, the progress ring shows the progress towards
+/// the `target` (in seconds, `0` for none).
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stopwatch {
+    pub target: u64,
+}
+
+impl Stopwatch {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        Program { blocks: vec![Block::Step(Step::open("Stopwatch", self.target))] }
+    }
+}
+
+/// The settings of a single countdown, e.g. for an AMRAP.
+///
+/// The `duration` is given in seconds, if `alarm` is set an alarm sounds when the time is up.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Countdown {
+    pub duration: u64,
+    pub alarm: bool,
+}
+
+impl Default for Countdown {
+    fn default() -> Self {
+        Self { duration: 12 * 60, alarm: true }
+    }
+}
+
+impl Countdown {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        Program { blocks: vec![Block::Step(Step::work("Countdown", self.duration))] }
+    }
+}
+
+/// The settings of an EMOM ("every minute on the minute") workout.
+///
+/// `rounds` periods of `period` seconds each, the work is done at the start of every period and
+/// whatever is left of it is rest. The optional `labels` name the exercises, they alternate from
+/// period to period (A, B, C, A, ...).
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Emom {
+    pub period: u64,
+    pub rounds: u64,
+    pub labels: Vec<String>,
+}
+
+impl Default for Emom {
+    fn default() -> Self {
+        Self { period: 60, rounds: 10, labels: Vec::new() }
+    }
+}
+
+impl Emom {
+    /// The exercise label of the given round (starting at `0`), if there are any labels.
+    pub fn label(&self, round: u64) -> Option<&str> {
+        if self.labels.is_empty() {
+            None
+        } else {
+            Some(&self.labels[round as usize % self.labels.len()])
+        }
+    }
+
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let blocks = (0..self.rounds)
+            .map(|i| Block::Step(Step::work(self.label(i).unwrap_or("EMOM"), self.period)))
+            .collect();
+        Program { blocks }
+    }
+}
+
+/// Name of the long break of a `Pomodoro`.
+pub const LONG_BREAK: &str = "Long break";
+
+/// The settings of a Pomodoro focus session.
+///
+/// `sessions` times `focus`, separated by short breaks and followed by a long break. All
+/// durations are given in seconds, if `auto_start` isn't set the timer waits for the user
+/// before every focus session and break.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pomodoro {
+    pub focus: u64,
+    pub short_break: u64,
+    pub long_break: u64,
+    pub sessions: u64,
+    pub auto_start: bool,
+}
+
+impl Default for Pomodoro {
+    fn default() -> Self {
+        Self {
+            focus: 25 * 60,
+            short_break: 5 * 60,
+            long_break: 15 * 60,
+            sessions: 4,
+            auto_start: false,
+        }
+    }
+}
+
+impl Pomodoro {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let mut blocks = vec![Block::Repeat {
+            times: self.sessions,
+            blocks: vec![Block::Step(Step::work("Focus", self.focus))],
+            rest: if self.short_break > 0 { Some(Step::rest("Short break", self.short_break)) } else { None },
+        }];
+
+        if self.long_break > 0 {
+            blocks.push(Block::Step(Step::rest(LONG_BREAK, self.long_break)));
+        }
+
+        Program { blocks }
+    }
+}
+
+/// The settings of a boxing (or any other combat sport) training.
+///
+/// `rounds` rounds of `round` seconds with `rest` seconds in between. A clapper sounds `warning`
+/// seconds before the end of every round (`0` for none).
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Boxing {
+    pub round: u64,
+    pub rest: u64,
+    pub rounds: u64,
+    pub warning: u64,
+}
+
+impl Default for Boxing {
+    fn default() -> Self {
+        Self { round: 180, rest: 60, rounds: 12, warning: 10 }
+    }
+}
+
+impl Boxing {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        Program {
+            blocks: vec![Block::Repeat {
+                times: self.rounds,
+                blocks: vec![Block::Step(Step::work("Round", self.round))],
+                rest: if self.rest > 0 { Some(Step::rest("Rest", self.rest)) } else { None },
+            }],
+        }
+    }
+}
+
+/// The settings of a breathing exercise.
+///
+/// `breaths` times inhale, hold, exhale and hold, all given in seconds. A hold of `0` seconds is
+/// omitted.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Breathing {
+    pub inhale: u64,
+    pub hold_full: u64,
+    pub exhale: u64,
+    pub hold_empty: u64,
+    pub breaths: u64,
+}
+
+impl Default for Breathing {
+    fn default() -> Self {
+        Self::pattern(4, 4, 4, 4)
+    }
+}
+
+impl Breathing {
+    /// Box breathing.
+    pub const BOX: [u64; 4] = [4, 4, 4, 4];
+    /// The relaxing breath.
+    pub const RELAXING: [u64; 4] = [4, 7, 8, 0];
+
+    /// Create a breathing exercise of ten breaths with the given phases (in seconds).
+    pub fn pattern(inhale: u64, hold_full: u64, exhale: u64, hold_empty: u64) -> Self {
+        Self { inhale, hold_full, exhale, hold_empty, breaths: 10 }
+    }
+
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let phases = [
+            (Breath::Inhale, self.inhale),
+            (Breath::HoldFull, self.hold_full),
+            (Breath::Exhale, self.exhale),
+            (Breath::HoldEmpty, self.hold_empty),
+        ];
+
+        Program {
+            blocks: vec![Block::Repeat {
+                times: self.breaths,
+                blocks: phases.iter()
+                    .filter(|(_, duration)| *duration > 0)
+                    .map(|(breath, duration)| Block::Step(Step::breath(*breath, *duration)))
+                    .collect(),
+                rest: None,
+            }],
+        }
+    }
+}
+
+/// The settings of a meditation.
+///
+/// A single period of `duration` seconds, a bell rings every `bell` seconds (`0` for none). If
+/// `hide_digits` is set the remaining time isn't shown.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Meditation {
+    pub duration: u64,
+    pub bell: u64,
+    pub hide_digits: bool,
+}
+
+impl Default for Meditation {
+    fn default() -> Self {
+        Self { duration: 20 * 60, bell: 5 * 60, hide_digits: false }
+    }
+}
+
+impl Meditation {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        Program { blocks: vec![Block::Step(Step::work("Meditation", self.duration))] }
+    }
+}
+
+/// Name of the transition between the two sides of a bilateral `Stretch`.
+pub const SWITCH_SIDES: &str = "Switch sides";
+
+/// A single stretch of a `Stretching` routine, held for `hold` seconds.
+///
+/// A `bilateral` stretch is held on the left and on the right side.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stretch {
+    pub name: String,
+    pub hold: u64,
+    pub bilateral: bool,
+}
+
+impl Default for Stretch {
+    fn default() -> Self {
+        Self { name: "Stretch".to_string(), hold: 30, bilateral: false }
+    }
+}
+
+/// The settings of a stretching routine.
+///
+/// The `stretches` are executed in order with `rest` seconds in between, the sides of a
+/// bilateral stretch are separated by a transition of `switch` seconds.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stretching {
+    pub stretches: Vec<Stretch>,
+    pub switch: u64,
+    pub rest: u64,
+}
+
+impl Default for Stretching {
+    fn default() -> Self {
+        let stretch = |name: &str, bilateral| Stretch { name: name.to_string(), hold: 30, bilateral };
+        Self {
+            stretches: vec![
+                stretch("Hamstring stretch", true),
+                stretch("Quad stretch", true),
+                stretch("Hip flexor stretch", true),
+                stretch("Child's pose", false),
+            ],
+            switch: 5,
+            rest: 10,
+        }
+    }
+}
+
+impl Stretching {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let mut blocks = Vec::new();
+
+        for (i, stretch) in self.stretches.iter().enumerate() {
+            if i > 0 && self.rest > 0 {
+                blocks.push(Block::Step(Step::rest("Rest", self.rest)));
+            }
+
+            if stretch.bilateral {
+                blocks.push(Block::Step(Step::work(&format!("{} (left)", stretch.name), stretch.hold)));
+                if self.switch > 0 {
+                    blocks.push(Block::Step(Step::rest(SWITCH_SIDES, self.switch)));
+                }
+                blocks.push(Block::Step(Step::work(&format!("{} (right)", stretch.name), stretch.hold)));
+            } else {
+                blocks.push(Block::Step(Step::work(&stretch.name, stretch.hold)));
+            }
+        }
+
+        Program { blocks }
+    }
+}
+
+/// The shape of a `Ladder`.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Shape {
+    Ascending,
+    Descending,
+    Pyramid,    // ascending, then descending
+}
+
+impl Shape {
+    /// All shapes, in the order they are offered to the user.
+    pub const ALL: [Shape; 3] = [Shape::Pyramid, Shape::Ascending, Shape::Descending];
+
+    /// The name of the shape shown to the user.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Ascending => "Ascending",
+            Shape::Descending => "Descending",
+            Shape::Pyramid => "Pyramid",
+        }
+    }
+}
+
+/// The settings of a ladder (or pyramid) workout.
+///
+/// The work intervals grow from `low` to `high` seconds by `increment` seconds (and shrink
+/// again, depending on the `shape`). The rest between two work intervals is either `rest`
+/// seconds or, if `proportional` is set, `rest` percent of the preceding work interval.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ladder {
+    pub low: u64,
+    pub high: u64,
+    pub increment: u64,
+    pub shape: Shape,
+    pub rest: u64,
+    pub proportional: bool,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Self {
+            low: 10,
+            high: 30,
+            increment: 10,
+            shape: Shape::Pyramid,
+            rest: 10,
+            proportional: false,
+        }
+    }
+}
+
+impl Ladder {
+    /// The maximum number of rungs on the way up (or down).
+    const MAX_RUNGS: usize = 100;
+
+    /// The durations of the work intervals in seconds, in order of execution.
+    pub fn rungs(&self) -> Vec<u64> {
+        let mut up = vec![self.low];
+        while self.increment > 0 && up.len() < Self::MAX_RUNGS {
+            match up[up.len() - 1].checked_add(self.increment) {
+                Some(next) if next <= self.high => up.push(next),
+                _ => break,
+            }
+        }
+
+        match self.shape {
+            Shape::Ascending => up,
+            Shape::Descending => up.into_iter().rev().collect(),
+            Shape::Pyramid => {
+                let down = up.iter().rev().skip(1).copied().collect::<Vec<u64>>();
+                up.extend(down);
+                up
+            },
+        }
+    }
+
+    /// The rest after a work interval of `work` seconds, `None` if it's out of range.
+    pub fn rest_after(&self, work: u64) -> Option<u64> {
+        if self.proportional {
+            Some(work.checked_mul(self.rest)?.checked_add(50)? / 100)
+        } else {
+            Some(self.rest)
+        }
+    }
+
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let rungs = self.rungs();
+        let mut blocks = Vec::new();
+
+        for (i, work) in rungs.iter().enumerate() {
+            blocks.push(Block::Step(Step::work("Work", *work)));
+
+            let rest = match self.rest_after(*work) {
+                Some(rest) => rest,
+                None => break, // the ladder ends here
+            };
+            if i + 1 < rungs.len() && rest > 0 {
+                blocks.push(Block::Step(Step::rest("Rest", rest)));
+            }
+        }
+
+        Program { blocks }
+    }
+}
+
+/// A step of a `Routine` as configured by the user.
+///
+/// A `manual` entry lasts until the user is done (its `duration` is ignored), a `rest` entry is
+/// executed as `Rest` step.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Entry {
+    pub name: String,
+    pub duration: u64,
+    pub manual: bool,
+    pub rest: bool,
+}
+
+impl Default for Entry {
+    fn default() -> Self {
+        Self { name: "Exercise".to_string(), duration: 30, manual: false, rest: false }
+    }
+}
+
+/// The settings of a custom routine, a list of timed and manual steps (e.g. a strength circuit).
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Routine {
+    pub entries: Vec<Entry>,
+}
+
+impl Default for Routine {
+    fn default() -> Self {
+        let entry = |name: &str, duration, manual, rest| Entry { name: name.to_string(), duration, manual, rest };
+        Self {
+            entries: vec![
+                entry("15 push-ups", 0, true, false),
+                entry("Rest", 30, false, true),
+                entry("Plank", 45, false, false),
+                entry("Rest", 30, false, true),
+                entry("20 squats", 0, true, false),
+            ],
+        }
+    }
+}
+
+impl Routine {
+    /// Turn the settings into an executable `Program`.
+    pub fn program(&self) -> Program {
+        let blocks = self.entries.iter().map(|entry| Block::Step(if entry.manual {
+            Step::manual(&entry.name)
+        } else if entry.rest {
+            Step::rest(&entry.name, entry.duration)
+        } else {
+            Step::work(&entry.name, entry.duration)
+        })).collect();
+
+        Program { blocks }
+    }
+}
+
+/// The settings of a circuit, the group rotates through the `stations`.
+///
+/// Each station is worked for `work` seconds followed by `rest` seconds to move to the next one.
+/// The circuit is repeated `rounds` times with `round_rest` seconds in between.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Circuit {
+    pub stations: Vec<String>,
+    pub work: u64,
+    pub rest: u64,
+    pub rounds: u64,
+    pub round_rest: u64,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        let stations = [
+            "Squats", "Push-ups", "Lunges", "Burpees", "Kettlebell swings", "Plank", "Jumping jacks", "Mountain climbers",
+        ];
+        Self {
+            stations: stations.iter().map(|s| s.to_string()).collect(),
+            work: 40,
+            rest: 20,
+            rounds: 2,
+            round_rest: 120,
+        }
+    }
+}
+
+impl Circuit {
+    /// Turn the settings into an executable `Program`.
+    ///
+    /// The work steps are named after their station, the rest steps announce the next one.
+    pub fn program(&self) -> Program {
+        let mut blocks = Vec::new();
+        let count = self.stations.len();
+
+        for round in 0..self.rounds {
+            for (i, station) in self.stations.iter().enumerate() {
+                blocks.push(Block::Step(Step::work(station, self.work)));
+
+                let last = i + 1 == count;
+                if last && round + 1 == self.rounds {
+                    break;
+                }
+
+                let rest = if last { self.round_rest } else { self.rest };
+                if rest > 0 {
+                    let next = &self.stations[(i + 1) % count];
+                    blocks.push(Block::Step(Step::rest(&format!("Next: {}", next), rest)));
+                }
+            }
+        }
+
+        Program { blocks }
+    }
+}
+
+/// The settings of a partner (or team) workout, the `participants` take turns.
+///
+/// `turns` work intervals of `work` seconds, each by the next participant. The others rest
+/// meanwhile, additionally everyone can rest `rest` seconds between two turns (`0` for none).
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Partner {
+    pub participants: Vec<String>,
+    pub work: u64,
+    pub rest: u64,
+    pub turns: u64,
+}
+
+impl Default for Partner {
+    fn default() -> Self {
+        Self {
+            participants: vec!["Partner A".to_string(), "Partner B".to_string()],
+            work: 60,
+            rest: 0,
+            turns: 10,
+        }
+    }
+}
+
+impl Partner {
+    /// The index of the participant whose turn it is in the given turn (starting at `0`).
+    pub fn turn(&self, turn: u64) -> usize {
+        turn as usize % self.participants.len().max(1)
+    }
+
+    /// Turn the settings into an executable `Program`, the work steps are named after the
+    /// participant.
+    pub fn program(&self) -> Program {
+        if self.participants.is_empty() {
+            return Program { blocks: Vec::new() };
+        }
+
+        let mut blocks = Vec::new();
+        for turn in 0..self.turns {
+            if turn > 0 && self.rest > 0 {
+                blocks.push(Block::Step(Step::rest("Rest", self.rest)));
+            }
+            blocks.push(Block::Step(Step::work(&self.participants[self.turn(turn)], self.work)));
+        }
+
+        Program { blocks }
+    }
+}
+
+/// How the time bank of a player grows with each turn of a `Chess` clock.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Increment {
+    None,
+    Fischer,    // the bonus is added after every turn
+    Bronstein,  // the time used is given back after every turn, up to the bonus
+}
+
+impl Increment {
+    /// All increments, in the order they are offered to the user.
+    pub const ALL: [Increment; 3] = [Increment::None, Increment::Fischer, Increment::Bronstein];
+
+    /// The name of the increment shown to the user.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Increment::None => "None",
+            Increment::Fischer => "Increment (Fischer)",
+            Increment::Bronstein => "Delay (Bronstein)",
+        }
+    }
+}
+
+/// The settings of a chess clock (or any other turn timer).
+///
+/// Each of the `players` has a time bank of `bank` seconds that only runs down during their own
+/// turns. Depending on the `increment`, `bonus` seconds are credited after every turn.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Chess {
+    pub players: Vec<String>,
+    pub bank: u64,
+    pub increment: Increment,
+    pub bonus: u64,
+}
+
+impl Default for Chess {
+    fn default() -> Self {
+        Self {
+            players: vec!["White".to_string(), "Black".to_string()],
+            bank: 300,
+            increment: Increment::Fischer,
+            bonus: 3,
+        }
+    }
+}
+
+impl Chess {
+    /// The milliseconds credited to the bank of a player after a turn that took `used`
+    /// milliseconds.
+    pub fn credit(&self, used: f64) -> f64 {
+        let bonus = (self.bonus * 1000) as f64;
+        match self.increment {
+            Increment::None => 0.0,
+            Increment::Fischer => bonus,
+            Increment::Bronstein => used.min(bonus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The names of the steps of a program, in order of execution.
+    fn names(program: &Program) -> Vec<String> {
+        program.steps().into_iter().map(|s| s.name).collect()
+    }
+
+    #[test]
+    fn flatten_repeats() {
+        let block = Block::Repeat {
+            times: 3,
+            blocks: vec![Block::Step(Step::work("On", 20)), Block::Step(Step::rest("Off", 10))],
+            rest: Some(Step::rest("Set rest", 60)),
+        };
+        let mut steps = Vec::new();
+        block.flatten(&mut steps);
+
+        let names = steps.iter().map(|s| s.name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names, vec!["On", "Off", "Set rest", "On", "Off", "Set rest", "On", "Off"]);
+    }
+
+    #[test]
+    fn flatten_nested_repeats() {
+        let inner = Block::Repeat {
+            times: 2,
+            blocks: vec![Block::Step(Step::work("A", 5))],
+            rest: Some(Step::rest("r", 1)),
+        };
+        let program = Program {
+            blocks: vec![
+                Block::Step(Step::rest("Warm-up", 30)),
+                Block::Repeat { times: 2, blocks: vec![inner], rest: Some(Step::rest("R", 10)) },
+                Block::Repeat { times: 0, blocks: vec![Block::Step(Step::work("Never", 5))], rest: None },
+            ],
+        };
+
+        assert_eq!(names(&program), vec!["Warm-up", "A", "r", "A", "R", "A", "r", "A"]);
+        assert_eq!(program.duration(), 30 + 4 * 5 + 2 + 10);
+    }
+
+    #[test]
+    fn workout_program() {
+        let workout = Workout { warm_up: 60, cycles: 2, sets: 2, set_rest: 30, cool_down: 0, ..Workout::default() };
+        let steps = workout.program().steps();
+
+        assert_eq!(steps.first(), Some(&Step::rest("Warm-up", 60)));
+        assert_eq!(steps.iter().filter(|s| s.kind == Kind::Work).count(), 4);
+        assert_eq!(steps.iter().filter(|s| s.duration == 30).count(), 1); // only between the sets
+        assert_eq!(steps.last().map(|s| s.kind), Some(Kind::Work));
+    }
+
+    #[test]
+    fn ladder_shapes() {
+        let ladder = |shape| Ladder { low: 10, high: 40, increment: 10, shape, ..Ladder::default() };
+
+        assert_eq!(ladder(Shape::Ascending).rungs(), vec![10, 20, 30, 40]);
+        assert_eq!(ladder(Shape::Descending).rungs(), vec![40, 30, 20, 10]);
+        assert_eq!(ladder(Shape::Pyramid).rungs(), vec![10, 20, 30, 40, 30, 20, 10]);
+    }
+
+    #[test]
+    fn ladder_edge_cases() {
+        // The top is left out if it can't be reached in whole increments.
+        let ladder = Ladder { low: 10, high: 35, increment: 10, shape: Shape::Ascending, ..Ladder::default() };
+        assert_eq!(ladder.rungs(), vec![10, 20, 30]);
+
+        let ladder = Ladder { low: 30, high: 10, ..Ladder::default() };
+        assert_eq!(ladder.rungs(), vec![30]);
+
+        let ladder = Ladder { increment: 0, ..Ladder::default() };
+        assert_eq!(ladder.rungs(), vec![10]);
+
+        let ladder = Ladder { low: 1, high: 1000, increment: 1, shape: Shape::Ascending, ..Ladder::default() };
+        assert_eq!(ladder.rungs().len(), Ladder::MAX_RUNGS);
+
+        let ladder = Ladder { low: u64::MAX - 1, high: u64::MAX, increment: u64::MAX, ..Ladder::default() };
+        assert_eq!(ladder.rungs(), vec![u64::MAX - 1]);
+    }
+
+    #[test]
+    fn ladder_program() {
+        let ladder = Ladder { proportional: true, rest: 50, ..Ladder::default() };
+        let durations = ladder.program().steps().iter().map(|s| s.duration).collect::<Vec<u64>>();
+
+        // No rest after the last rung.
+        assert_eq!(durations, vec![10, 5, 20, 10, 30, 15, 20, 10, 10]);
+    }
+
+    #[test]
+    fn ladder_rest_out_of_range() {
+        let ladder = Ladder { low: 10, high: 20, rest: u64::MAX, proportional: true, ..Ladder::default() };
+        assert_eq!(ladder.rest_after(10), None);
+        assert_eq!(ladder.program().steps(), vec![Step::work("Work", 10)]);
+    }
+
+    #[test]
+    fn circuit_program() {
+        let circuit = Circuit {
+            stations: vec!["A".to_string(), "B".to_string()],
+            work: 40,
+            rest: 20,
+            rounds: 2,
+            round_rest: 120,
+        };
+        let steps = circuit.program().steps();
+
+        assert_eq!(steps, vec![
+            Step::work("A", 40),
+            Step::rest("Next: B", 20),
+            Step::work("B", 40),
+            Step::rest("Next: A", 120),
+            Step::work("A", 40),
+            Step::rest("Next: B", 20),
+            Step::work("B", 40),
+        ]);
+    }
+
+    #[test]
+    fn circuit_without_rest() {
+        let circuit = Circuit { stations: vec!["A".to_string()], rest: 0, round_rest: 0, rounds: 3, ..Circuit::default() };
+        assert_eq!(names(&circuit.program()), vec!["A", "A", "A"]);
+
+        let circuit = Circuit { stations: Vec::new(), ..Circuit::default() };
+        assert!(circuit.program().steps().is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::services::storage::{Area, StorageService};
+
+use super::engine::Edit;
+use super::settings::{restore, Settings};
+
+/// Key of the running `Session` in the local storage.
+const KEY_SESSION: &str = "rustytimer.session";
+
+/// A checkpoint of a running workout, used to resume it after the page has been reloaded.
+///
+/// All timestamps are wall-clock timestamps (milliseconds since the epoch, see `Date.now()`)
+/// because monotonic timestamps don't survive a reload.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub settings: Settings,
+    pub started_at: f64,        // timestamp the workout has been started at
+    pub paused: f64,            // total milliseconds the workout has been paused
+    pub paused_at: Option<f64>, // timestamp the workout has been paused at (if it is paused)
+    #[serde(default)]
+    pub edits: Vec<(f64, Edit)>, // edits made by the user, with the milliseconds the workout had been running
+}
+
+impl Session {
+    /// Create a new session that has been started at `now`.
+    pub fn new(settings: Settings, now: f64) -> Self {
+        Self { settings, started_at: now, paused: 0.0, paused_at: None, edits: Vec::new() }
+    }
+
+    /// Milliseconds the workout has been running at `now` (pauses not counted).
+    pub fn elapsed(&self, now: f64) -> f64 {
+        self.paused_at.unwrap_or(now) - self.started_at - self.paused
+    }
+
+    /// Mark the session as paused at `now`.
+    pub fn pause(&mut self, now: f64) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(now);
+        }
+    }
+
+    /// Mark the session as resumed at `now`.
+    pub fn resume(&mut self, now: f64) {
+        if let Some(at) = self.paused_at.take() {
+            self.paused += now - at;
+        }
+    }
+
+    /// Note an edit made to the workout at `now`, so it can be made again when the workout is
+    /// resumed.
+    pub fn edit(&mut self, edit: Edit, now: f64) {
+        let at = self.elapsed(now);
+        self.edits.push((at, edit));
+    }
+
+    /// Restore the checkpoint from the local storage.
+    pub fn load() -> Option<Self> {
+        restore(KEY_SESSION)
+    }
+
+    /// Save the checkpoint to the local storage.
+    pub fn save(&self) {
+        if let Ok(mut storage) = StorageService::new(Area::Local) {
+            storage.store(KEY_SESSION, Json(self));
+        }
+    }
+
+    /// Remove the checkpoint from the local storage.
+    pub fn clear() {
+        if let Ok(mut storage) = StorageService::new(Area::Local) {
+            storage.remove(KEY_SESSION);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::services::storage::{Area, StorageService};
+
+use super::engine::Cues;
+use super::program::{
+    Boxing, Breathing, Chess, Circuit, Countdown, Emom, Ladder, Meditation, Partner, Pomodoro, Program,
+    Routine, Stopwatch, Stretching, Workout,
+};
+
+/// Key of the last-used `Settings` in the local storage.
+const KEY_SETTINGS: &str = "rustytimer.settings";
+/// Key of the `Preset` library in the local storage.
+const KEY_PRESETS: &str = "rustytimer.presets";
+
+/// The different modes of the timer.
+///
+/// # Modes
+///
+/// * `Interval` - Loop between work and rest (see `Workout`).
+/// * `Stopwatch` - Count up and take lap times (see `Stopwatch`).
+/// * `Countdown` - Count down once (see `Countdown`).
+/// * `Emom` - Start a new round every minute (see `Emom`).
+/// * `Pomodoro` - Focus sessions with short and long breaks (see `Pomodoro`).
+/// * `Boxing` - Rounds with a warning before the end of each (see `Boxing`).
+/// * `Breathing` - Guided breathing (see `Breathing`).
+/// * `Meditation` - A long period with interval bells (see `Meditation`).
+/// * `Stretching` - A routine of stretches, optionally for both sides (see `Stretching`).
+/// * `Ladder` - Work intervals of growing and/or shrinking length (see `Ladder`).
+/// * `Routine` - A custom list of timed and manual steps (see `Routine`).
+/// * `Circuit` - Rotate through a number of stations (see `Circuit`).
+/// * `Partner` - Participants take turns (see `Partner`).
+/// * `Chess` - Players take turns, each with their own time bank (see `Chess`).
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum Mode {
+    #[default]
+    Interval,
+    Stopwatch,
+    Countdown,
+    Emom,
+    Pomodoro,
+    Boxing,
+    Breathing,
+    Meditation,
+    Stretching,
+    Ladder,
+    Routine,
+    Circuit,
+    Partner,
+    Chess,
+}
+
+impl Mode {
+    /// All modes, in the order they are offered to the user.
+    pub const ALL: [Mode; 14] = [
+        Mode::Interval,
+        Mode::Ladder,
+        Mode::Emom,
+        Mode::Circuit,
+        Mode::Partner,
+        Mode::Chess,
+        Mode::Boxing,
+        Mode::Routine,
+        Mode::Countdown,
+        Mode::Stopwatch,
+        Mode::Pomodoro,
+        Mode::Stretching,
+        Mode::Breathing,
+        Mode::Meditation,
+    ];
+
+    /// The name of the mode shown to the user.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Interval => "Interval",
+            Mode::Stopwatch => "Stopwatch",
+            Mode::Countdown => "Countdown",
+            Mode::Emom => "EMOM",
+            Mode::Pomodoro => "Pomodoro",
+            Mode::Boxing => "Boxing",
+            Mode::Breathing => "Breathing",
+            Mode::Meditation => "Meditation",
+            Mode::Stretching => "Stretching",
+            Mode::Ladder => "Ladder / Pyramid",
+            Mode::Routine => "Custom routine",
+            Mode::Circuit => "Circuit",
+            Mode::Partner => "Partner / Team",
+            Mode::Chess => "Chess clock",
+        }
+    }
+}
+
+/// Everything the user can configure through the settings `Form`.
+///
+/// Each mode keeps its own settings, so nothing is lost when switching between them. Missing
+/// fields are filled with their defaults, so settings stored by older versions can be restored.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub mode: Mode,
+    pub workout: Workout,
+    pub stopwatch: Stopwatch,
+    pub countdown: Countdown,
+    pub emom: Emom,
+    pub pomodoro: Pomodoro,
+    pub boxing: Boxing,
+    pub breathing: Breathing,
+    pub meditation: Meditation,
+    pub stretching: Stretching,
+    pub ladder: Ladder,
+    pub routine: Routine,
+    pub circuit: Circuit,
+    pub partner: Partner,
+    pub chess: Chess,
+    pub cues: Cues,
+}
+
+/// A named set of `Settings` saved by the user.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub settings: Settings,
+}
+
+impl Settings {
+    /// The program to execute for the selected mode.
+    pub fn program(&self) -> Program {
+        match self.mode {
+            Mode::Interval => self.workout.program(),
+            Mode::Stopwatch => self.stopwatch.program(),
+            Mode::Countdown => self.countdown.program(),
+            Mode::Emom => self.emom.program(),
+            Mode::Pomodoro => self.pomodoro.program(),
+            Mode::Boxing => self.boxing.program(),
+            Mode::Breathing => self.breathing.program(),
+            Mode::Meditation => self.meditation.program(),
+            Mode::Stretching => self.stretching.program(),
+            Mode::Ladder => self.ladder.program(),
+            Mode::Routine => self.routine.program(),
+            Mode::Circuit => self.circuit.program(),
+            Mode::Partner => self.partner.program(),
+            Mode::Chess => Program { blocks: Vec::new() }, // the turns are kept by the `TurnClock`
+        }
+    }
+
+    /// The cues to emit, including those that are part of the selected mode.
+    pub fn cues(&self) -> Cues {
+        let mut cues = self.cues.clone();
+        match self.mode {
+            Mode::Countdown => cues.one_minute = true, // warn before the time is up
+            Mode::Boxing => cues.warning = self.boxing.warning,
+            Mode::Breathing => cues.beeps = 0, // a breath is too short for a countdown
+            Mode::Meditation => { // only bells, no beeps
+                cues.beeps = 0;
+                cues.bell = self.meditation.bell;
+            },
+            _ => {},
+        }
+        cues
+    }
+
+    /// Whether the next step starts right away when a step is over.
+    pub fn auto_start(&self) -> bool {
+        self.mode != Mode::Pomodoro || self.pomodoro.auto_start
+    }
+
+    /// Restore the last-used settings from the local storage.
+    ///
+    /// Falls back to the default settings if nothing (or nothing valid) is stored.
+    pub fn load() -> Self {
+        restore(KEY_SETTINGS).unwrap_or_default()
+    }
+
+    /// Save the settings to the local storage.
+    pub fn save(&self) {
+        if let Ok(mut storage) = StorageService::new(Area::Local) {
+            storage.store(KEY_SETTINGS, Json(self));
+        }
+    }
+}
+
+impl Preset {
+    /// Restore the preset library from the local storage.
+    pub fn load_all() -> Vec<Preset> {
+        restore(KEY_PRESETS).unwrap_or_default()
+    }
+
+    /// Save the preset library to the local storage.
+    pub fn save_all(presets: &[Preset]) {
+        if let Ok(mut storage) = StorageService::new(Area::Local) {
+            storage.store(KEY_PRESETS, Json(&presets));
+        }
+    }
+}
+
+/// Restore a value from the local storage, `None` if it doesn't exist or can't be parsed.
+pub fn restore<T>(key: &str) -> Option<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let storage = StorageService::new(Area::Local).ok()?;
+    let Json(value) = storage.restore(key);
+    value.ok()
+}
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use yew::callback::Callback;
+use yew::format::{Json, Text};
+
+use super::engine::Cues;
+use super::program::{
+    Boxing, Breathing, Chess, Circuit, Countdown, Emom, Ladder, Meditation, Partner, Pomodoro,
+    Routine, Stopwatch, Stretching, Workout,
+};
+use super::settings::{Mode, Settings};
+
+/// Prefix of the url fragment that carries shared settings.
+const PREFIX: &str = "workout=";
+
+#[wasm_bindgen]
+extern "C" {
+    /// The fragment of the current url, see `location.hash`.
+    fn location_hash() -> String;
+
+    /// Remove the fragment from the url of the current page, without reloading it.
+    fn clear_location_hash();
+
+    /// Copy a link to the current page with the given fragment to the clipboard, `done` is
+    /// called with `true` on success.
+    fn copy_share_link(fragment: &str, done: &JsValue);
+}
+
+/// The part of the `Settings` that is shared: the mode, the settings of that mode and the cues.
+///
+/// It's decoded as `Settings`, the settings of all other modes are left at their defaults.
+#[derive(Serialize)]
+struct Shared<'a> {
+    mode: Mode,
+    #[serde(flatten)]
+    part: Part<'a>,
+    cues: &'a Cues,
+}
+
+/// The settings of a single mode, serialized under the name of the field of `Settings`.
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Part<'a> {
+    Workout(&'a Workout),
+    Stopwatch(&'a Stopwatch),
+    Countdown(&'a Countdown),
+    Emom(&'a Emom),
+    Pomodoro(&'a Pomodoro),
+    Boxing(&'a Boxing),
+    Breathing(&'a Breathing),
+    Meditation(&'a Meditation),
+    Stretching(&'a Stretching),
+    Ladder(&'a Ladder),
+    Routine(&'a Routine),
+    Circuit(&'a Circuit),
+    Partner(&'a Partner),
+    Chess(&'a Chess),
+}
+
+impl<'a> Shared<'a> {
+    /// Pick the parts of the settings to share.
+    fn new(settings: &'a Settings) -> Self {
+        let part = match settings.mode {
+            Mode::Interval => Part::Workout(&settings.workout),
+            Mode::Stopwatch => Part::Stopwatch(&settings.stopwatch),
+            Mode::Countdown => Part::Countdown(&settings.countdown),
+            Mode::Emom => Part::Emom(&settings.emom),
+            Mode::Pomodoro => Part::Pomodoro(&settings.pomodoro),
+            Mode::Boxing => Part::Boxing(&settings.boxing),
+            Mode::Breathing => Part::Breathing(&settings.breathing),
+            Mode::Meditation => Part::Meditation(&settings.meditation),
+            Mode::Stretching => Part::Stretching(&settings.stretching),
+            Mode::Ladder => Part::Ladder(&settings.ladder),
+            Mode::Routine => Part::Routine(&settings.routine),
+            Mode::Circuit => Part::Circuit(&settings.circuit),
+            Mode::Partner => Part::Partner(&settings.partner),
+            Mode::Chess => Part::Chess(&settings.chess),
+        };
+        Self { mode: settings.mode, part, cues: &settings.cues }
+    }
+}
+
+/// Encode the settings of the selected mode as url fragment (without the leading `#`).
+pub fn encode(settings: &Settings) -> Option<String> {
+    let text: Text = Json(&Shared::new(settings)).into();
+    text.ok().map(|json| format!("{}{}", PREFIX, percent_encode(&json)))
+}
+
+/// Decode settings from an url fragment (with or without the leading `#`).
+///
+/// Returns `None` if the fragment doesn't carry (valid) settings.
+pub fn decode(fragment: &str) -> Option<Settings> {
+    let json = percent_decode(fragment.trim_start_matches('#').strip_prefix(PREFIX)?)?;
+    let Json(settings) = Ok(json).into();
+    settings.ok()
+}
+
+/// The settings shared via the url of the current page, if any.
+pub fn from_location() -> Option<Settings> {
+    decode(&location_hash())
+}
+
+/// Remove the shared settings from the url of the current page once they are applied, so they
+/// don't overwrite later changes when the page is reloaded.
+pub fn clear_location() {
+    clear_location_hash();
+}
+
+/// Copy a link to the given settings to the clipboard, `done` is invoked with `true` if the link
+/// has been copied.
+pub fn copy_link(settings: &Settings, done: Callback<bool>) {
+    match encode(settings) {
+        Some(fragment) => {
+            let done = Closure::once_into_js(move |copied: bool| done.emit(copied));
+            copy_share_link(&fragment, &done);
+        },
+        None => done.emit(false),
+    }
+}
+
+/// Escape all characters except the unreserved ones (`A-Z a-z 0-9 - _ . ~`) as `%XX`.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Reverse `percent_encode`, `None` if the input isn't valid.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_round_trip() {
+        let s = "{\"name\": \"Café #1 / 50%\"}";
+        let encoded = percent_encode(s);
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || "-_.~%".contains(c)));
+        assert_eq!(percent_decode(&encoded).as_deref(), Some(s));
+    }
+
+    #[test]
+    fn percent_decode_invalid() {
+        assert_eq!(percent_decode("%"), None);
+        assert_eq!(percent_decode("%4"), None);
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(percent_decode("%FF"), None); // not utf-8
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let mut settings = Settings { mode: Mode::Circuit, ..Settings::default() };
+        settings.circuit.rounds = 5;
+        settings.cues.beeps = 2;
+        settings.boxing.rounds = 3; // another mode, not shared
+
+        let decoded = decode(&format!("#{}", encode(&settings).unwrap())).unwrap();
+        assert_eq!(decoded.mode, Mode::Circuit);
+        assert_eq!(decoded.circuit, settings.circuit);
+        assert_eq!(decoded.cues, settings.cues);
+        assert_eq!(decoded.boxing, Settings::default().boxing);
+    }
+
+    #[test]
+    fn only_the_selected_mode_is_encoded() {
+        let settings = Settings::default();
+        let fragment = encode(&settings).unwrap();
+        let json = percent_decode(fragment.strip_prefix(PREFIX).unwrap()).unwrap();
+        assert!(json.contains("\"workout\""));
+        assert!(!json.contains("\"circuit\""));
+    }
+
+    #[test]
+    fn decode_rejects_other_fragments() {
+        assert!(decode("").is_none());
+        assert!(decode("#top").is_none());
+        assert!(decode("#workout=%7B").is_none());
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use yew::format::Json;
+use yew::services::storage::{Area, StorageService};
+
+use super::settings::restore;
+
+/// Key of the `Tally` in the local storage.
+const KEY_TALLY: &str = "rustytimer.tally";
+
+#[wasm_bindgen]
+extern "C" {
+    /// The current local date, see `Date.toDateString()`.
+    fn today() -> String;
+}
+
+/// The number of pomodoros completed today.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Tally {
+    pub day: String,    // the day the pomodoros have been completed on
+    pub count: u64,
+}
+
+impl Tally {
+    /// Restore today's tally from the local storage, a new day starts from zero.
+    pub fn load() -> Self {
+        let day = today();
+        match restore::<Tally>(KEY_TALLY) {
+            Some(tally) if tally.day == day => tally,
+            _ => Self { day, count: 0 },
+        }
+    }
+
+    /// Count a completed pomodoro and save the tally to the local storage.
+    pub fn increment(&mut self) {
+        let day = today();
+        if self.day != day { // Midnight has passed
+            self.day = day;
+            self.count = 0;
+        }
+        self.count += 1;
+
+        if let Ok(mut storage) = StorageService::new(Area::Local) {
+            storage.store(KEY_TALLY, Json(&*self));
+        }
+    }
+}
@@ -0,0 +1,916 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew::services::{Task, IntervalService, RenderService};
+use yew::services::keyboard::{KeyboardService, KeyListenerHandle};
+use yew::services::render::RenderTask;
+use yew::web_sys::{Element, KeyboardEvent};
+
+use super::clock::{Clock, Segment};
+use super::duration;
+use super::engine::{Edit, Engine, Event, State, Cue};
+use super::program::{Breath, Kind, LONG_BREAK, SWITCH_SIDES};
+use super::settings::{Mode, Settings};
+use super::session::Session;
+use super::tally::Tally;
+use super::turns::TurnClock;
+use wasm_bindgen::__rt::core::time::Duration;
+
+/// Color of the progress rings while working out.
+const WORK_COLOR: &str = "#39c9bb";
+/// Color of the progress rings while resting.
+const REST_COLOR: &str = "#f0ad4e";
+/// Color of the progress rings during a Pomodoro focus session.
+const FOCUS_COLOR: &str = "#e8554e";
+/// Color of the progress rings during the long break of a Pomodoro.
+const LONG_BREAK_COLOR: &str = "#5b9bd5";
+/// Color of the breathing circle.
+const BREATH_COLOR: &str = "#7fb3d5";
+/// Colors of the participants of a partner workout, in turn.
+const PARTICIPANT_COLORS: [&str; 6] = ["#39c9bb", "#e8554e", "#5b9bd5", "#f7c948", "#9b6fd1", "#6fcf57"];
+
+
+/// This represents the upper layer of an interval timer.
+///
+/// A interval timer (also known as ta-ba-ta timer) executes the steps of a workout `Program`,
+/// e.g. it loops between `on` and `off` state until all cycles of a training are completed. The
+/// actual state machine lives in the `Engine`, the `Timer` frequently feeds it the current time
+/// and turns its events into sounds.
+///
+/// While the timer is running it is rendered on every animation frame. Because browsers don't
+/// deliver animation frames to hidden tabs, an additional `IntervalService` keeps the `Engine`
+/// (and the sounds) going in the background.
+///
+/// A running workout of the primary timer is checkpointed as `Session` so it can be resumed
+/// after a reload. Any number of timers can be hosted side by side, see `Dashboard`.
+pub struct Timer {
+    link: ComponentLink<Self>,
+    engine: Engine,
+    turns: TurnClock,           // the time banks of the players (chess clock)
+    props: Props,
+    settings: Settings,         // the settings in use (those of a resumed workout)
+    session: Option<Session>,   // checkpoint of the current workout
+    recovered: Option<Session>, // checkpoint of a workout interrupted by a reload
+    tally: Tally,               // pomodoros completed today
+    tallied: u64,               // focus sessions of the current workout counted (or skipped)
+    callback_tick: Callback<()>, // callback to be invoked on a `tick`
+    callback_frame: Callback<f64>, // callback to be invoked on an animation frame
+    message: &'static str,
+    job: Option<Box<dyn Task>>, // Currently active task
+    frame: Option<RenderTask>,  // Currently requested animation frame
+    _key_listener: Option<KeyListenerHandle>, // primary timer only
+}
+
+/// Messages the `Timer` can handle.
+///
+/// # Messages
+///
+/// * `StartTimer` - Starts the timer.
+/// * `StopTimer` - Stops the timer (state is preserved).
+/// * `ResetTimer` - Resets everything to the currently selected settings.
+/// * `Configure` - Apply new settings (workout program and cues).
+/// * `ResumeSession` - Resume the workout interrupted by a reload.
+/// * `DiscardSession` - Discard the workout interrupted by a reload.
+/// * `Lap` - Take a split time (stopwatch).
+/// * `Done` - Complete the current manual step.
+/// * `Pass` - Pass the turn to the next player (chess clock).
+/// * `Skip` - Skip to the next interval.
+/// * `Back` - Go back to the previous interval.
+/// * `AddTime` - Add (or subtract, if negative) seconds to the current interval.
+/// * `ExtraRound` - Add another round at the end of the workout.
+/// * `KeyDown` - A key has been pressed, space and enter complete a manual step or pass the turn.
+/// * `Tick` - Frequently called (every 250ms) by an `IntervalService` if the timer is active (`On`, `Off`, `Waiting`).
+/// * `Frame` - Called on every animation frame if the timer is active.
+pub enum Msg {
+    StartTimer,
+    StopTimer,
+    ResetTimer,
+    Configure(Box<Settings>),
+    ResumeSession,
+    DiscardSession,
+    Lap,
+    Done,
+    Pass,
+    Skip,
+    Back,
+    AddTime(i64),
+    ExtraRound,
+    KeyDown(KeyboardEvent),
+    Tick,
+    Frame,
+}
+
+/// Properties of the `Timer`.
+///
+/// * `settings` - The settings of the timer, the timer is reset whenever they change.
+/// * `primary` - If set to true, the workout is checkpointed and manual steps can be completed
+///   with the keyboard. There should be only one primary timer per page.
+/// * `start_all` - Incremented by the parent to start (or resume) all of its timers at once.
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub settings: Settings,
+    #[prop_or_default]
+    pub primary: bool,
+    #[prop_or_default]
+    pub start_all: u64,
+}
+
+impl Timer {
+    /// Bring the `Engine` (or the `TurnClock` of a chess clock) up to date with the given
+    /// timestamp and react to its events.
+    fn advance(&mut self, now: f64) {
+        if self.chess() {
+            let events = self.turns.tick(now);
+            self.react(events);
+        } else {
+            self.drive(|engine| engine.tick(now));
+        }
+    }
+
+    /// Operate the `Engine` and react to the events it emits.
+    fn drive<F>(&mut self, operate: F)
+    where
+        F: FnOnce(&mut Engine) -> Vec<Event>,
+    {
+        let events = operate(&mut self.engine);
+        self.react(events);
+
+        // Each focus session counts once, even if it is gone back to and completed again.
+        if self.settings.mode == Mode::Pomodoro {
+            for _ in self.tallied..self.engine.completed() {
+                self.tally.increment();
+            }
+        }
+        self.tallied = self.tallied.max(self.engine.completed());
+
+        // The engine pauses by itself between two steps if they aren't started automatically.
+        if self.engine.state() == State::Paused && self.job.is_some() {
+            if let Some(mut session) = self.session.take() {
+                // The step has been over for a moment (or a while, in a hidden tab).
+                let late = now() - self.engine.paused_at();
+                session.pause(date_now() - late);
+                self.checkpoint(Some(session));
+            }
+            self.message = "Resume when you are ready";
+            self.job = None;
+            self.frame = None;
+        }
+    }
+
+    /// Play the sounds and set the message for the events emitted by the `Engine` (or the
+    /// `TurnClock`).
+    fn react(&mut self, events: Vec<Event>) {
+        for event in events {
+            match event {
+                Event::Countdown(0) if self.settings.mode == Mode::Breathing => play_countdown("Breath"),
+                Event::Countdown(0) if self.settings.mode == Mode::Meditation => play_countdown("Bell"),
+                Event::Countdown(0) if self.switching() => {}, // see `IntervalStarted`
+                Event::Countdown(0) => play_countdown("LongBeep"),
+                Event::Countdown(_) => play_countdown("ShortBeep"),
+                Event::Cue(Cue::Halfway) => {
+                    play_countdown("Halfway");
+                    self.message = "Halfway";
+                },
+                Event::Cue(Cue::LastRound) => {
+                    play_countdown("LastRound");
+                    self.message = "Last round!";
+                },
+                Event::Cue(Cue::OneMinuteLeft) => {
+                    play_countdown("OneMinute");
+                    self.message = "1 minute left";
+                },
+                Event::Cue(Cue::TenSecondsLeft) => {
+                    play_countdown("TenSeconds");
+                    self.message = "10 seconds left";
+                },
+                Event::Cue(Cue::Warning) => {
+                    play_countdown("Clapper");
+                    self.message = "Finish strong!";
+                },
+                Event::Cue(Cue::Bell) => play_countdown("Bell"),
+                Event::Finished if self.chess() => { // A player ran out of time
+                    play_countdown("Alarm");
+                    self.message = "Time's up!";
+                    self.job = None;
+                    self.frame = None;
+                },
+                Event::Finished if self.settings.mode == Mode::Countdown => {
+                    if self.settings.countdown.alarm {
+                        play_countdown("Alarm");
+                    }
+                    self.message = "Time's up!";
+                    self.checkpoint(None);
+                    self.job = None;
+                    self.frame = None;
+                },
+                Event::Finished => { // All steps completed, Nice Job !
+                    self.message = "Done, nice work!";
+                    self.checkpoint(None);
+                    self.job = None;
+                    self.frame = None;
+                },
+                Event::IntervalStarted(_) if self.switching() => {
+                    play_countdown("SwitchSides");
+                    self.message = "Switch sides";
+                },
+                Event::IntervalStarted(_) => self.message = "",
+            }
+        }
+    }
+
+    /// Make a change to the running workout and note it in the checkpoint, so it's made again
+    /// when the workout is resumed after a reload.
+    fn edit(&mut self, edit: Edit) {
+        let t = now();
+        if edit == Edit::Skip {
+            self.advance(t);
+            if self.engine.state() == State::On { // A skipped focus session doesn't count
+                self.tallied = self.tallied.max(self.engine.completed() + 1);
+            }
+        }
+        self.drive(|engine| engine.edit(edit, t));
+        if let Some(mut session) = self.session.take() {
+            session.edit(edit, date_now());
+            self.checkpoint(Some(session));
+        }
+    }
+
+    /// The color of the progress rings for the current phase.
+    fn color(&self) -> &'static str {
+        if self.settings.mode == Mode::Pomodoro {
+            match self.engine.step() {
+                Some(step) if step.name == LONG_BREAK => LONG_BREAK_COLOR,
+                Some(step) if step.kind == Kind::Rest => REST_COLOR,
+                _ => FOCUS_COLOR,
+            }
+        } else if self.settings.mode == Mode::Breathing {
+            BREATH_COLOR
+        } else if let Some(participant) = self.participant() {
+            PARTICIPANT_COLORS[participant % PARTICIPANT_COLORS.len()]
+        } else if self.engine.state() == State::Off {
+            REST_COLOR
+        } else {
+            WORK_COLOR
+        }
+    }
+
+    /// The text of the `Clock`.
+    fn clock_text(&self) -> String {
+        if self.settings.mode == Mode::Meditation && self.settings.meditation.hide_digits {
+            String::new()
+        } else if self.engine.state() == State::Start {
+            format!("{}", self.engine.remaining())
+        } else if self.settings.mode == Mode::Stopwatch {
+            duration::format_elapsed(self.engine.phase_elapsed(), true)
+        } else if self.waiting() {
+            duration::format_elapsed(self.engine.phase_elapsed(), false)
+        } else {
+            duration::clock_face(self.engine.remaining_ms())
+        }
+    }
+
+    /// The caption of the `Clock`.
+    fn clock_label(&self) -> String {
+        match self.engine.state() {
+            State::Start => "Get ready".to_string(),
+            State::Idle => String::new(),
+            _ if self.settings.mode == Mode::Emom => self.round_label(),
+            _ if self.settings.mode == Mode::Boxing && self.working() => self.round_label(),
+            _ => self.engine.step().map_or(String::new(), |s| s.name.clone()),
+        }
+    }
+
+    /// The number of the current round out of all rounds, e.g. `Round 3/10`, followed by the
+    /// exercise label if there is one (EMOM).
+    fn round_label(&self) -> String {
+        let round = (self.engine.completed() + 1).min(self.engine.cycles());
+        let label = format!("Round {}/{}", round, self.engine.cycles());
+        let exercise = match self.settings.mode {
+            Mode::Emom => self.settings.emom.label(round.saturating_sub(1)),
+            _ => None,
+        };
+        match exercise {
+            Some(exercise) => format!("{} · {}", label, exercise),
+            None => label,
+        }
+    }
+
+    /// The phase of the breath if a breathing exercise is running.
+    fn breath(&self) -> Option<Breath> {
+        match self.engine.state() {
+            State::Start | State::Idle => None,
+            _ => self.engine.step().and_then(|s| s.breath),
+        }
+    }
+
+    /// True if the text of the `Clock` should be greyed out: while resting, and all the time
+    /// during a meditation.
+    fn darken(&self) -> bool {
+        match self.engine.state() {
+            State::Off => self.breath().is_none(),
+            State::Idle => false,
+            _ => self.settings.mode == Mode::Meditation,
+        }
+    }
+
+    /// True if the sides of a bilateral stretch are being switched.
+    fn switching(&self) -> bool {
+        self.engine.state() != State::Idle && self.engine.step().is_some_and(|s| s.name == SWITCH_SIDES)
+    }
+
+    /// True if the current step is a manual step (even if paused).
+    fn waiting(&self) -> bool {
+        match self.engine.state() {
+            State::Start | State::Idle => false,
+            _ => self.engine.step().is_some_and(|s| s.manual),
+        }
+    }
+
+    /// The index of the participant whose turn it is (partner workout only).
+    fn participant(&self) -> Option<usize> {
+        match self.engine.state() {
+            State::Start | State::Idle => None,
+            _ if self.settings.mode == Mode::Partner && self.working() => {
+                Some(self.settings.partner.turn(self.engine.completed()))
+            },
+            _ => None,
+        }
+    }
+
+    /// True if the timer is a chess clock, i.e. the `TurnClock` is used instead of the `Engine`.
+    fn chess(&self) -> bool {
+        self.settings.mode == Mode::Chess
+    }
+
+    /// The state of the `Engine`, or of the `TurnClock` of a chess clock.
+    fn state(&self) -> State {
+        if self.chess() {
+            self.turns.state()
+        } else {
+            self.engine.state()
+        }
+    }
+
+    /// True if the current step is a `Work` step.
+    fn working(&self) -> bool {
+        self.engine.step().is_some_and(|s| s.kind == Kind::Work)
+    }
+
+    /// True if the clock should flash, i.e. the warning of a boxing round is due. The clock
+    /// blinks twice a second until the round is over.
+    fn flash(&self) -> bool {
+        let warning = self.settings.boxing.warning as f64 * 1000.0;
+        let left = self.engine.remaining_ms();
+        self.settings.mode == Mode::Boxing && self.engine.state() == State::On
+            && left <= warning && left % 500.0 < 250.0
+    }
+
+    /// The segments of the outer progress ring, one for each cycle of the workout (none if
+    /// there is only one).
+    fn segments(&self) -> Vec<Segment> {
+        let completed = self.engine.completed();
+        let running = self.engine.state() != State::Idle;
+        if self.engine.cycles() <= 1 {
+            return Vec::new();
+        }
+
+        (0..self.engine.cycles()).map(|i| Segment {
+            progress: if i < completed {
+                1.0
+            } else if i == completed {
+                self.engine.cycle_progress()
+            } else {
+                0.0
+            },
+            color: match self.settings.mode {
+                Mode::Pomodoro => FOCUS_COLOR,
+                Mode::Partner => PARTICIPANT_COLORS[self.settings.partner.turn(i) % PARTICIPANT_COLORS.len()],
+                _ => WORK_COLOR,
+            },
+            active: running && i == completed,
+        }).collect()
+    }
+
+    /// Keep the `Engine` going: spawn the `IntervalService` and request animation frames.
+    fn run(&mut self) {
+        // Create an new `IntervalService` instance that calls `Tick` every 250ms. The `Engine`
+        // only relies on the timestamps, so the interval doesn't have to be exact.
+        let handle = IntervalService::spawn(Duration::from_millis(250), self.callback_tick.clone());
+        self.job = Some(Box::new(handle));
+        self.request_frame();
+    }
+
+    /// Replace the checkpoint of the current workout and write it to the local storage (primary
+    /// timer only).
+    fn checkpoint(&mut self, session: Option<Session>) {
+        if self.props.primary {
+            match &session {
+                Some(session) => session.save(),
+                None => Session::clear(),
+            }
+        }
+        self.session = session;
+    }
+
+    /// Create the table of lap times (stopwatch).
+    fn view_laps(&self) -> Html {
+        let laps = self.engine.laps();
+        if laps.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <table class="table table-sm table-dark mx-auto mb-3" style="max-width: 24em;">
+                <thead>
+                    <tr><th>{ "Lap" }</th><th>{ "Time" }</th><th>{ "Split" }</th></tr>
+                </thead>
+                <tbody>
+                    { for laps.iter().enumerate().rev().map(|(i, split)| {
+                        let previous = if i == 0 { 0.0 } else { laps[i - 1] };
+                        html! {
+                            <tr>
+                                <td>{ i + 1 }</td>
+                                <td>{ duration::format_elapsed(split - previous, true) }</td>
+                                <td>{ duration::format_elapsed(*split, true) }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+
+    /// Create the table of the time taken for each manual step.
+    fn view_records(&self) -> Html {
+        let records = self.engine.records();
+        if records.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <table class="table table-sm table-dark mx-auto mb-3" style="max-width: 24em;">
+                <thead>
+                    <tr><th>{ "Step" }</th><th>{ "Time" }</th></tr>
+                </thead>
+                <tbody>
+                    { for records.iter().map(|record| html! {
+                        <tr>
+                            <td>{ &record.name }</td>
+                            <td>{ duration::format_elapsed(record.time, true) }</td>
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+
+    /// Create the list of stations, the current one (or the next one during a rest) is
+    /// highlighted (circuit only).
+    fn view_circuit(&self) -> Html {
+        let stations = &self.settings.circuit.stations;
+        if self.settings.mode != Mode::Circuit || stations.is_empty() {
+            return html! {};
+        }
+
+        let completed = self.engine.completed() as usize;
+        let current = match self.engine.state() {
+            State::Idle => None,
+            _ => Some(completed % stations.len()),
+        };
+        let round = (completed / stations.len() + 1).min(self.settings.circuit.rounds as usize);
+
+        html! {
+            <div class="mb-3">
+                <p class="mb-1">{ format!("Round {}/{}", round, self.settings.circuit.rounds) }</p>
+                <ol class="list-group mx-auto" style="max-width: 24em;">
+                    { for stations.iter().enumerate().map(|(i, station)| html! {
+                        <li class={ if current == Some(i) { "list-group-item list-group-item-info py-1" } else { "list-group-item list-group-item-dark py-1" } }>
+                            { format!("{}. {}", i + 1, station) }
+                        </li>
+                    }) }
+                </ol>
+            </div>
+        }
+    }
+
+    /// Create the summary of the work completed by each participant at the end of a partner
+    /// workout.
+    fn view_summary(&self) -> Html {
+        let partner = &self.settings.partner;
+        let work = self.engine.work();
+        if self.settings.mode != Mode::Partner || self.engine.state() != State::Idle || work.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <table class="table table-sm table-dark mx-auto mb-3" style="max-width: 24em;">
+                <thead>
+                    <tr><th>{ "Participant" }</th><th>{ "Turns" }</th><th>{ "Work" }</th></tr>
+                </thead>
+                <tbody>
+                    { for partner.participants.iter().enumerate().map(|(p, name)| {
+                        let turns = work.iter().enumerate().filter(|(t, _)| partner.turn(*t as u64) == p);
+                        let (count, time) = turns.fold((0, 0.0), |(count, time), (_, ms)| (count + 1, time + ms));
+                        html! {
+                            <tr>
+                                <td style={ format!("color: {};", PARTICIPANT_COLORS[p % PARTICIPANT_COLORS.len()]) }>{ name }</td>
+                                <td>{ count }</td>
+                                <td>{ duration::format_elapsed(time, false) }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+
+    /// Create the list of upcoming stretches (stretching only).
+    fn view_upcoming(&self) -> Html {
+        if self.settings.mode != Mode::Stretching {
+            return html! {};
+        }
+
+        html! {
+            <ul class="list-unstyled text-muted mb-3">
+                { for self.engine.upcoming().iter().filter(|s| s.kind == Kind::Work).take(5).map(|step| html! {
+                    <li>{ format!("{} · {}", step.name, duration::format(step.duration)) }</li>
+                }) }
+            </ul>
+        }
+    }
+
+    /// Show the number of pomodoros completed today (Pomodoro mode only).
+    fn view_tally(&self) -> Html {
+        if self.settings.mode != Mode::Pomodoro {
+            return html! {};
+        }
+
+        html! { <p class="text-muted">{ format!("Pomodoros today: {}", self.tally.count) }</p> }
+    }
+
+    /// Create the buttons to control the timer.
+    fn view_controls(&self) -> Html {
+        match self.engine.state() {
+            State::Idle if self.recovered.is_some() => html! { <><p>{ "Resume where you left off?" }</p>
+                                     <button type="button" class="btn btn-outline-info btn-lg mr-3" onclick=self.link.callback(|_| Msg::ResumeSession)>{ "Resume" }</button>
+                                     <button type="button" class="btn btn-outline-warning btn-lg" onclick=self.link.callback(|_| Msg::DiscardSession)>{ "Discard" }</button></>},
+            State::Idle => html! { <button type="button" class="btn btn-outline-info btn-lg" onclick=self.link.callback(|_| Msg::StartTimer)>{ "Start" }</button> },
+            State::Paused => html! { <><button type="button" class="btn btn-outline-info btn-lg mr-3" onclick=self.link.callback(|_| Msg::StartTimer)>{ "Resume" }</button>
+                                     <button type="button" class="btn btn-outline-warning btn-lg" onclick=self.link.callback(|_| Msg::ResetTimer)>{ "Reset" }</button></>},
+            State::Start => html! { },
+            State::Waiting => html! { <><button type="button" class="btn btn-outline-info btn-lg mr-3" title="Space or Enter" onclick=self.link.callback(|_| Msg::Done)>{ "Done" }</button>
+                                     <button type="button" class="btn btn-outline-secondary btn-lg" onclick=self.link.callback(|_| Msg::StopTimer)>{ "Stop" }</button></>},
+            _ if self.settings.mode == Mode::Stopwatch => html! { <><button type="button" class="btn btn-outline-info btn-lg mr-3" onclick=self.link.callback(|_| Msg::Lap)>{ "Lap" }</button>
+                                     <button type="button" class="btn btn-outline-secondary btn-lg" onclick=self.link.callback(|_| Msg::StopTimer)>{ "Stop" }</button></>},
+            _ => html! { <button type="button" class="btn btn-outline-secondary btn-lg" onclick=self.link.callback(|_| Msg::StopTimer)>{ "Stop" }</button> },
+        }
+    }
+
+    /// Create the clock of the player on turn, the time banks of all players and the buttons of
+    /// a chess clock. Tapping the clock passes the turn.
+    fn view_chess(&self) -> Html {
+        let player = self.turns.player();
+        let name = self.settings.chess.players.get(player).cloned().unwrap_or_default();
+
+        html! {
+            <>
+                <p class="lead">{ self.message }</p>
+                <div class="clock-container" onclick=self.link.callback(|_| Msg::Pass)>
+                    <Clock progress={ self.turns.progress() }
+                           text={ duration::clock_face(self.turns.remaining_ms(player)) }
+                           label={ name }
+                           darken={ self.turns.state() == State::Paused }
+                           color={ PARTICIPANT_COLORS[player % PARTICIPANT_COLORS.len()] }
+                    />
+                </div>
+
+                { self.view_banks() }
+                { match self.turns.state() {
+                    State::Idle => html! { <button type="button" class="btn btn-outline-info btn-lg" onclick=self.link.callback(|_| Msg::StartTimer)>{ "Start" }</button> },
+                    State::Paused => html! { <><button type="button" class="btn btn-outline-info btn-lg mr-3" onclick=self.link.callback(|_| Msg::StartTimer)>{ "Resume" }</button>
+                                             <button type="button" class="btn btn-outline-warning btn-lg" onclick=self.link.callback(|_| Msg::ResetTimer)>{ "Reset" }</button></>},
+                    _ => html! { <><button type="button" class="btn btn-outline-info btn-lg mr-3" title="Space or Enter" onclick=self.link.callback(|_| Msg::Pass)>{ "Next" }</button>
+                                 <button type="button" class="btn btn-outline-secondary btn-lg" onclick=self.link.callback(|_| Msg::StopTimer)>{ "Stop" }</button></>},
+                } }
+            </>
+        }
+    }
+
+    /// Create the table of the time left to each player (chess clock).
+    fn view_banks(&self) -> Html {
+        let on_turn = match self.turns.state() {
+            State::Idle => None,
+            _ => Some(self.turns.player()),
+        };
+
+        html! {
+            <table class="table table-sm table-dark mx-auto mb-3" style="max-width: 24em;">
+                <thead>
+                    <tr><th>{ "Player" }</th><th>{ "Time" }</th><th>{ "Turns" }</th></tr>
+                </thead>
+                <tbody>
+                    { for self.settings.chess.players.iter().enumerate().map(|(p, name)| html! {
+                        <tr class={ if on_turn == Some(p) { "table-active" } else { "" } }>
+                            <td style={ format!("color: {};", PARTICIPANT_COLORS[p % PARTICIPANT_COLORS.len()]) }>{ name }</td>
+                            <td>{ if self.turns.flagged() == Some(p) { "Out of time".to_string() } else { duration::clock_face(self.turns.remaining_ms(p)) } }</td>
+                            <td>{ self.turns.turns(p) }</td>
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+
+    /// Create the buttons to skip, rewind and adjust the running workout (not for a stopwatch).
+    fn view_adjust(&self) -> Html {
+        match self.engine.state() {
+            State::Start | State::On | State::Off | State::Waiting if self.settings.mode != Mode::Stopwatch => {},
+            _ => return html! {},
+        }
+
+        html! {
+            <div class="btn-group mt-3" role="group">
+                <button type="button" class="btn btn-outline-light" title="Previous interval" onclick=self.link.callback(|_| Msg::Back)>
+                    <i class="fa fa-step-backward" aria-hidden="true"></i>
+                </button>
+                <button type="button" class="btn btn-outline-light" onclick=self.link.callback(|_| Msg::AddTime(-10))>{ "-10s" }</button>
+                <button type="button" class="btn btn-outline-light" onclick=self.link.callback(|_| Msg::AddTime(10))>{ "+10s" }</button>
+                <button type="button" class="btn btn-outline-light" title="Next interval" onclick=self.link.callback(|_| Msg::Skip)>
+                    <i class="fa fa-step-forward" aria-hidden="true"></i>
+                </button>
+                { if self.engine.cycles() > 1 {
+                    html! { <button type="button" class="btn btn-outline-light" onclick=self.link.callback(|_| Msg::ExtraRound)>{ "+1 round" }</button> }
+                } else {
+                    html! {}
+                } }
+            </div>
+        }
+    }
+
+    /// Request the next animation frame if the timer is active.
+    fn request_frame(&mut self) {
+        self.frame = match self.state() {
+            State::Idle | State::Paused => None,
+            _ => Some(RenderService::request_animation_frame(self.callback_frame.clone())),
+        };
+    }
+}
+
+impl Component for Timer {
+    type Message = Msg;
+    type Properties = Props;
+
+    /// Create a new `Timer` component.
+    ///
+    /// # Arguments
+    ///
+    /// * `props` - Properties passed by the `Dashboard`.
+    /// * `link` - A link to register callbacks or send messages to the component.
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let settings = props.settings.clone();
+        let key_listener = if props.primary {
+            Some(KeyboardService::register_key_down(&yew::utils::document(), link.callback(Msg::KeyDown)))
+        } else {
+            None
+        };
+        let recovered = if props.primary { Session::load() } else { None };
+        let mut engine = Engine::new(&settings.program());
+        engine.set_cues(settings.cues());
+        engine.set_auto_start(settings.auto_start());
+        let mut turns = TurnClock::new(&settings.chess);
+        turns.set_beeps(settings.cues().beeps);
+
+        Self {
+            callback_tick: link.callback(|_| Msg::Tick), // register new `Tick` callback.
+            callback_frame: link.callback(|_: f64| Msg::Frame),
+            link,
+            engine,
+            turns,
+            props,
+            settings,
+            session: None,
+            recovered,
+            tally: Tally::load(),
+            tallied: 0,
+            message: "",
+            job: None,
+            frame: None,
+            _key_listener: key_listener,
+        }
+    }
+
+    /// Handle incomming messages.
+    ///
+    /// The `update()` lifecycle method is called for each message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to handle.
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            // Called when the timer is started or resumed.
+            Msg::StartTimer if self.chess() => { // Chess clocks aren't checkpointed
+                self.turns.start(now());
+                if self.turns.state() == State::Idle { // No players
+                    return false;
+                }
+                self.message = "";
+                self.run();
+            },
+            Msg::StartTimer => {
+                if self.engine.state() == State::Idle {
+                    self.tallied = 0;
+                }
+                self.engine.start(now());
+                if self.engine.state() == State::Idle { // Nothing to execute
+                    return false;
+                }
+
+                let session = match self.session.take() {
+                    Some(mut session) => { // Resume timer
+                        session.resume(date_now());
+                        session
+                    },
+                    None => Session::new(self.settings.clone(), date_now()), // Start timer
+                };
+                self.checkpoint(Some(session));
+                self.recovered = None;
+                self.message = "";
+                self.run();
+            },
+            Msg::StopTimer if self.chess() => {
+                let t = now();
+                self.advance(t);
+                self.turns.stop(t);
+                self.job = None;
+                self.frame = None;
+            },
+            Msg::StopTimer => { // Pause the timer (state is preserved until it is started again or reset)
+                let t = now();
+                self.advance(t);
+                self.engine.stop(t);
+                if let Some(mut session) = self.session.take() {
+                    session.pause(date_now());
+                    self.checkpoint(Some(session));
+                }
+                self.job = None; // Remove the current interval service that calls tick
+                self.frame = None;
+            },
+            Msg::ResetTimer => { // Reset the timer state
+                self.engine.reset();
+                self.tallied = 0;
+                self.turns.reset();
+                self.checkpoint(None);
+                self.message = "";
+                self.job = None;
+                self.frame = None;
+            },
+            Msg::Configure(settings) => {
+                self.engine.configure(&settings.program());
+                self.engine.set_cues(settings.cues());
+                self.engine.set_auto_start(settings.auto_start());
+                self.turns.configure(&settings.chess);
+                self.turns.set_beeps(settings.cues().beeps);
+                self.settings = *settings;
+                self.link.callback(|_| Msg::ResetTimer).emit(());
+            },
+            Msg::ResumeSession => {
+                if let Some(mut session) = self.recovered.take() {
+                    self.engine.configure(&session.settings.program());
+                    self.engine.set_cues(session.settings.cues());
+                    self.engine.set_auto_start(session.settings.auto_start());
+                    self.settings = session.settings.clone();
+
+                    let t = now();
+                    self.engine.restore(session.elapsed(date_now()), &session.edits, t);
+                    if session.paused_at.is_some() {
+                        self.engine.stop(t);
+                    }
+
+                    self.tallied = self.engine.completed(); // counted before the reload
+
+                    if self.engine.state() == State::Idle { // Completed in the meantime
+                        self.checkpoint(None);
+                    } else {
+                        if self.engine.state() == State::Paused { // Paused, or waiting for the next step
+                            session.pause(date_now());
+                        } else {
+                            self.run();
+                        }
+                        self.checkpoint(Some(session));
+                    }
+                }
+            },
+            Msg::DiscardSession => {
+                self.recovered = None;
+                Session::clear();
+            },
+            Msg::Lap => {
+                self.advance(now());
+                self.engine.lap();
+            },
+            Msg::Done => {
+                self.edit(Edit::Done);
+                self.request_frame();
+            },
+            Msg::Pass => {
+                let events = self.turns.pass(now());
+                self.react(events);
+            },
+            Msg::Skip => self.edit(Edit::Skip),
+            Msg::Back => self.edit(Edit::Back),
+            Msg::AddTime(secs) => self.edit(Edit::Adjust(secs)),
+            Msg::ExtraRound => self.edit(Edit::ExtraRound),
+            Msg::KeyDown(e) => {
+                if (e.key() != " " && e.key() != "Enter") || typing(&e) {
+                    return false;
+                }
+                let msg = match self.state() {
+                    State::Waiting => Msg::Done,
+                    State::On if self.chess() => Msg::Pass,
+                    _ => return false,
+                };
+                e.prevent_default(); // don't click a focused button as well
+                self.link.send_message(msg);
+            },
+            Msg::Tick => { // Called frequently to update the timer state
+                self.advance(now());
+            },
+            Msg::Frame => { // Called on every animation frame to update the timer state
+                self.advance(now());
+                self.request_frame();
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props.settings != self.props.settings {
+            self.link.send_message(Msg::Configure(Box::new(props.settings.clone())));
+        }
+        if props.start_all != self.props.start_all {
+            match self.state() {
+                State::Idle | State::Paused => self.link.send_message(Msg::StartTimer),
+                _ => {}, // already running
+            }
+        }
+        self.props = props;
+        false
+    }
+
+    /// Create a (html) layout for the component.
+    fn view(&self) -> Html {
+        if self.chess() {
+            return self.view_chess();
+        }
+
+        html! {
+            <>
+                <p class="lead">{ self.message }</p>
+                <div class="clock-container">
+                    <Clock progress={ self.engine.phase_progress() }
+                           segments={ self.segments() }
+                           text={ self.clock_text() }
+                           label={ self.clock_label() }
+                           darken={ self.darken() }
+                           color={ self.color() }
+                           flash={ self.flash() }
+                           breath={ self.breath() }
+                    />
+                </div>
+
+                { self.view_laps() }
+                { self.view_records() }
+                { self.view_upcoming() }
+                { self.view_circuit() }
+                { self.view_summary() }
+                { self.view_tally() }
+                { self.view_controls() }
+                { self.view_adjust() }
+            </>
+        }
+    }
+}
+
+/// True if the key has been pressed in a form field (e.g. of the settings), in which case it
+/// belongs to the field.
+fn typing(e: &KeyboardEvent) -> bool {
+    match e.target().and_then(|target| target.dyn_into::<Element>().ok()) {
+        Some(element) => matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"),
+        None => false,
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    fn play_countdown(aid: &str);
+
+    /// Monotonic timestamp in milliseconds, see `performance.now()`.
+    #[wasm_bindgen(js_namespace = performance)]
+    fn now() -> f64;
+
+    /// Wall-clock timestamp in milliseconds since the epoch, see `Date.now()`.
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    fn date_now() -> f64;
+}
@@ -0,0 +1,292 @@
+use crate::engine::{Event, State};
+use crate::program::Chess;
+
+/// The state machine of a chess clock (turn timer), independent of any framework.
+///
+/// Each player has a time bank that only runs down during their own turns, passing the turn
+/// stops it and starts the bank of the next player. Like the `Engine`, the `TurnClock` derives
+/// its state from the timestamps passed to `start`, `stop`, `pass` and `tick`.
+///
+/// The clock is `On` while a player is on turn and `Paused` while it is paused. It's `Idle`
+/// before the first turn and after a player ran out of time.
+pub struct TurnClock {
+    chess: Chess,           // players, time bank and increment
+    beeps: u64,             // number of countdown beeps before a bank runs out
+    banks: Vec<f64>,        // milliseconds left to each player at the start of their turn
+    turns: Vec<u64>,        // completed turns of each player
+    player: usize,          // index of the player on turn
+    turn_start: f64,        // timestamp the current turn started at (not counting pauses)
+    paused_at: f64,         // timestamp the clock has been paused at
+    elapsed: f64,           // milliseconds into the current turn at the last tick
+    flagged: Option<usize>, // the player that ran out of time
+    state: State,
+}
+
+impl TurnClock {
+    /// Create a new `TurnClock` in `Idle` state.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess` - The players, time bank and increment.
+    pub fn new(chess: &Chess) -> Self {
+        let mut clock = Self {
+            chess: chess.clone(),
+            beeps: 0,
+            banks: Vec::new(),
+            turns: Vec::new(),
+            player: 0,
+            turn_start: 0.0,
+            paused_at: 0.0,
+            elapsed: 0.0,
+            flagged: None,
+            state: State::Idle,
+        };
+        clock.reset();
+        clock
+    }
+
+    /// Set new players, time bank and increment.
+    ///
+    /// The new settings take effect after the next `reset`.
+    pub fn configure(&mut self, chess: &Chess) {
+        self.chess = chess.clone();
+    }
+
+    /// Set the number of countdown beeps before a bank runs out.
+    pub fn set_beeps(&mut self, beeps: u64) {
+        self.beeps = beeps;
+    }
+
+    /// Start the first turn if the clock is `Idle` or resume it if it is `Paused`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn start(&mut self, now: f64) {
+        match self.state {
+            State::Idle => {
+                if self.chess.players.is_empty() {
+                    return;
+                }
+
+                self.reset();
+                self.turn_start = now;
+                self.state = State::On;
+            },
+            State::Paused => {
+                self.turn_start += now - self.paused_at;
+                self.state = State::On;
+            },
+            _ => {},
+        }
+    }
+
+    /// Pause the clock (state is preserved until it is started again or reset).
+    ///
+    /// Call `tick` with the same timestamp right before, so the clock is paused at the exact
+    /// millisecond.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn stop(&mut self, now: f64) {
+        if self.state == State::On {
+            self.paused_at = now;
+            self.state = State::Paused;
+        }
+    }
+
+    /// Reset everything to the currently configured settings.
+    pub fn reset(&mut self) {
+        let players = self.chess.players.len();
+        self.banks = vec![(self.chess.bank * 1000) as f64; players];
+        self.turns = vec![0; players];
+        self.player = 0;
+        self.elapsed = 0.0;
+        self.flagged = None;
+        self.state = State::Idle;
+    }
+
+    /// End the turn of the current player, credit the increment to their bank and start the
+    /// turn of the next player.
+    ///
+    /// Returns the events that occurred since the last tick, followed by
+    /// `IntervalStarted(On)` if the turn has been passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn pass(&mut self, now: f64) -> Vec<Event> {
+        let mut events = self.tick(now);
+        if self.state != State::On {
+            return events;
+        }
+
+        let used = self.elapsed;
+        self.banks[self.player] += self.chess.credit(used) - used;
+        self.turns[self.player] += 1;
+        self.player = (self.player + 1) % self.banks.len();
+        self.turn_start = now;
+        self.elapsed = 0.0;
+        events.push(Event::IntervalStarted(State::On));
+        events
+    }
+
+    /// Bring the clock up to date with the given timestamp.
+    ///
+    /// Returns the countdown beeps crossed since the last tick, followed by `Finished` if the
+    /// player on turn ran out of time.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current timestamp in milliseconds.
+    pub fn tick(&mut self, now: f64) -> Vec<Event> {
+        let mut events = Vec::new();
+        if self.state != State::On {
+            return events;
+        }
+
+        let last = self.elapsed;
+        let bank = self.banks[self.player];
+        self.elapsed = (now - self.turn_start).max(last).min(bank);
+        let crossed = |at: f64| at > last && at <= self.elapsed;
+
+        for left in (1..=self.beeps).rev() {
+            if crossed(bank - (left * 1000) as f64) {
+                events.push(Event::Countdown(left));
+            }
+        }
+
+        if self.elapsed >= bank { // Out of time
+            self.flagged = Some(self.player);
+            self.state = State::Idle;
+            events.push(Event::Finished);
+        }
+
+        events
+    }
+
+    /// Get the current state of the clock.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// The index of the player on turn.
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// The player that ran out of time, if any.
+    pub fn flagged(&self) -> Option<usize> {
+        self.flagged
+    }
+
+    /// Milliseconds left in the bank of the given player.
+    pub fn remaining_ms(&self, player: usize) -> f64 {
+        let bank = self.banks.get(player).copied().unwrap_or(0.0);
+        if player == self.player {
+            (bank - self.elapsed).max(0.0)
+        } else {
+            bank
+        }
+    }
+
+    /// The number of turns the given player has completed.
+    pub fn turns(&self, player: usize) -> u64 {
+        self.turns.get(player).copied().unwrap_or(0)
+    }
+
+    /// The fraction of the initial time bank the player on turn has used up, between `0.0`
+    /// and `1.0` (an increment may raise a bank above the initial time).
+    pub fn progress(&self) -> f64 {
+        let bank = (self.chess.bank * 1000) as f64;
+        if bank <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.remaining_ms(self.player) / bank).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::Increment;
+
+    /// A clock for two players with a 60 second bank and a 5 second bonus.
+    fn clock(increment: Increment) -> TurnClock {
+        let chess = Chess { players: vec!["A".to_string(), "B".to_string()], bank: 60, increment, bonus: 5 };
+        TurnClock::new(&chess)
+    }
+
+    #[test]
+    fn passing_switches_players() {
+        let mut clock = clock(Increment::None);
+        clock.start(0.0);
+        assert_eq!(clock.state(), State::On);
+
+        assert_eq!(clock.pass(10000.0), vec![Event::IntervalStarted(State::On)]);
+        assert_eq!(clock.player(), 1);
+        assert_eq!(clock.remaining_ms(0), 50000.0);
+
+        clock.tick(14000.0);
+        assert_eq!(clock.remaining_ms(1), 56000.0);
+        clock.pass(14000.0);
+        assert_eq!(clock.player(), 0);
+        assert_eq!(clock.turns(0), 1);
+        assert_eq!(clock.turns(1), 1);
+    }
+
+    #[test]
+    fn fischer_increment() {
+        let mut clock = clock(Increment::Fischer);
+        clock.start(0.0);
+        clock.pass(2000.0);
+        clock.pass(22000.0);
+
+        assert_eq!(clock.remaining_ms(0), 63000.0); // quick move, bonus on top
+        assert_eq!(clock.remaining_ms(1), 45000.0);
+    }
+
+    #[test]
+    fn bronstein_delay() {
+        let mut clock = clock(Increment::Bronstein);
+        clock.start(0.0);
+        clock.pass(2000.0);
+        clock.pass(22000.0);
+
+        assert_eq!(clock.remaining_ms(0), 60000.0); // the time used is given back
+        assert_eq!(clock.remaining_ms(1), 45000.0); // up to the bonus
+    }
+
+    #[test]
+    fn pauses_are_not_counted() {
+        let mut clock = clock(Increment::None);
+        clock.start(0.0);
+        clock.tick(10000.0);
+        clock.stop(10000.0);
+        assert!(clock.pass(30000.0).is_empty());
+        assert_eq!(clock.player(), 0);
+
+        clock.start(40000.0);
+        clock.tick(45000.0);
+        assert_eq!(clock.remaining_ms(0), 45000.0);
+    }
+
+    #[test]
+    fn running_out_of_time() {
+        let mut clock = clock(Increment::None);
+        clock.set_beeps(3);
+        clock.start(0.0);
+        clock.pass(1000.0);
+
+        assert_eq!(clock.tick(59500.0), vec![Event::Countdown(3), Event::Countdown(2)]);
+        assert_eq!(clock.tick(70000.0), vec![Event::Countdown(1), Event::Finished]);
+        assert_eq!(clock.state(), State::Idle);
+        assert_eq!(clock.flagged(), Some(1));
+        assert_eq!(clock.remaining_ms(1), 0.0);
+
+        // The game is over, passing has no effect.
+        assert!(clock.pass(71000.0).is_empty());
+        assert_eq!(clock.player(), 1);
+    }
+}